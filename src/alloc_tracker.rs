@@ -0,0 +1,301 @@
+// Tracking Allocator ---
+// The prose says `drop` gives the heap memory of a String back at the closing brace. This allocator proves it with numbers:
+// it wraps the system allocator and, while `track` runs a function, records every allocation and deallocation it makes.
+//
+// It is opt-in: it only records anything once a binary installs it with
+//
+//     #[global_allocator]
+//     static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+//
+// and only on the thread running `track`, so other threads don't show up in the timeline.
+//
+// `track` keeps the events apart from what the function prints. `stream` writes each event to stderr as it happens
+// instead: in a child process whose stdout and stderr are the same pipe, the events and the lines the function prints
+// come out in the order they happened, and `Timeline::read` puts them back together.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+// How many events `track` can record, the log can't grow since growing it would allocate.
+const CAPACITY: usize = 4096;
+
+// Starts the lines `stream` writes the events on, so they can't be taken for something the function printed.
+const EVENT_MARK: &str = "\u{1e}alloc ";
+
+pub struct TrackingAllocator;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Alloc,
+    Free,
+    /// A buffer grown or shrunk, possibly moved to `ptr`.
+    Realloc { from: usize, old_size: usize },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Event {
+    pub kind: Kind,
+    pub ptr: usize,
+    pub size: usize,
+}
+
+impl Event {
+    const EMPTY: Event = Event { kind: Kind::Alloc, ptr: 0, size: 0 };
+}
+
+struct Log {
+    events: [Event; CAPACITY],
+    len: usize,
+    /// Events that didn't fit.
+    lost: usize,
+}
+
+static LOG: Mutex<Log> = Mutex::new(Log { events: [Event::EMPTY; CAPACITY], len: 0, lost: 0 });
+
+// Set while `stream` runs: the events go to stderr rather than to the log.
+static STREAMING: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static TRACKING: Cell<bool> = const { Cell::new(false) };
+}
+
+fn record(event: Event) {
+    // The thread local can be gone while the thread exits, and then there is nothing to track.
+    if !TRACKING.try_with(Cell::get).unwrap_or(false) {
+        return;
+    }
+    if STREAMING.load(Ordering::Relaxed) {
+        return write_event(event);
+    }
+
+    let mut log = LOG.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if log.len < CAPACITY {
+        let len = log.len;
+        log.events[len] = event;
+        log.len += 1;
+    } else {
+        log.lost += 1;
+    }
+}
+
+// Writes an event on a line of its own, formatted on the stack since allocating here would record another event.
+fn write_event(event: Event) {
+    let mut line = [0u8; 128];
+    let mut cursor = io::Cursor::new(&mut line[..]);
+    let written = match event.kind {
+        Kind::Alloc => writeln!(cursor, "{}alloc {} {}", EVENT_MARK, event.ptr, event.size),
+        Kind::Free => writeln!(cursor, "{}free {} {}", EVENT_MARK, event.ptr, event.size),
+        Kind::Realloc { from, old_size } => {
+            writeln!(cursor, "{}realloc {} {} {} {}", EVENT_MARK, event.ptr, event.size, from, old_size)
+        }
+    };
+    if written.is_err() {
+        return;
+    }
+
+    let len = cursor.position() as usize;
+    // Whatever writing to stderr allocates is not the function's doing.
+    TRACKING.with(|tracking| tracking.set(false));
+    let _ = io::stderr().write_all(&line[..len]);
+    TRACKING.with(|tracking| tracking.set(true));
+}
+
+// The other way around: an event from what follows `EVENT_MARK` on its line.
+fn read_event(line: &str) -> Option<Event> {
+    let mut words = line.split(' ');
+    let name = words.next()?;
+    let numbers: Vec<usize> = words.map(str::parse).collect::<Result<_, _>>().ok()?;
+    let kind = match (name, numbers.as_slice()) {
+        ("alloc", [_, _]) => Kind::Alloc,
+        ("free", [_, _]) => Kind::Free,
+        ("realloc", [_, _, from, old_size]) => Kind::Realloc { from: *from, old_size: *old_size },
+        _ => return None,
+    };
+    Some(Event { kind, ptr: numbers[0], size: numbers[1] })
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(Event { kind: Kind::Alloc, ptr: ptr as usize, size: layout.size() });
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(Event { kind: Kind::Alloc, ptr: ptr as usize, size: layout.size() });
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(Event { kind: Kind::Free, ptr: ptr as usize, size: layout.size() });
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            let kind = Kind::Realloc { from: ptr as usize, old_size: layout.size() };
+            record(Event { kind, ptr: new as usize, size: new_size });
+        }
+        new
+    }
+}
+
+/// Everything a function allocated and freed, in order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timeline {
+    pub events: Vec<Event>,
+    /// Events that happened but didn't fit in the log.
+    pub lost: usize,
+    /// The lines the function printed, each with how many events happened before it. Only `read` knows them.
+    pub prints: Vec<(usize, String)>,
+}
+
+/// Runs `f` and records the allocations it makes on this thread.
+///
+/// The timeline is empty if `TrackingAllocator` is not the global allocator, see `is_installed`.
+pub fn track<R>(f: impl FnOnce() -> R) -> (R, Timeline) {
+    {
+        let mut log = LOG.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        log.len = 0;
+        log.lost = 0;
+    }
+
+    TRACKING.with(|tracking| tracking.set(true));
+    let result = f();
+    TRACKING.with(|tracking| tracking.set(false));
+
+    let log = LOG.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let timeline = Timeline { events: log.events[..log.len].to_vec(), lost: log.lost, prints: Vec::new() };
+    (result, timeline)
+}
+
+/// Runs `f` and writes the allocations it makes on this thread to stderr as they happen, each on a line of its own.
+///
+/// Run with stdout and stderr on the same pipe, what comes out of it is read back with `Timeline::read`.
+pub fn stream<R>(f: impl FnOnce() -> R) -> R {
+    // Stdout allocates its buffer the first time it is used, before the function rather than at its first print.
+    let _ = io::stdout().flush();
+
+    STREAMING.store(true, Ordering::Relaxed);
+    TRACKING.with(|tracking| tracking.set(true));
+    let result = f();
+    // Stdout writes a line as soon as it ends, this only matters for a last line that doesn't.
+    let _ = io::stdout().flush();
+    TRACKING.with(|tracking| tracking.set(false));
+    STREAMING.store(false, Ordering::Relaxed);
+    result
+}
+
+/// Whether the running binary uses `TrackingAllocator` as its global allocator.
+pub fn is_installed() -> bool {
+    let ((), timeline) = track(|| drop(std::hint::black_box(Box::new(0u64))));
+    !timeline.events.is_empty()
+}
+
+impl Timeline {
+    /// Reads back what a function run by `stream` wrote: the events, and the lines it printed in between.
+    pub fn read(output: &str) -> Timeline {
+        let mut timeline = Timeline { events: Vec::new(), lost: 0, prints: Vec::new() };
+        for line in output.lines() {
+            match line.strip_prefix(EVENT_MARK).and_then(read_event) {
+                Some(event) => timeline.events.push(event),
+                None => timeline.prints.push((timeline.events.len(), line.to_string())),
+            }
+        }
+        timeline
+    }
+
+    /// Bytes still allocated when the function returned: allocated minus freed.
+    pub fn live_bytes(&self) -> isize {
+        self.events
+            .iter()
+            .map(|event| match event.kind {
+                Kind::Alloc => event.size as isize,
+                Kind::Free => -(event.size as isize),
+                Kind::Realloc { old_size, .. } => event.size as isize - old_size as isize,
+            })
+            .sum()
+    }
+
+    // The allocation a pointer belongs to, by its position in the timeline: allocations are numbered in order.
+    fn numbers(&self) -> Vec<usize> {
+        let mut live: Vec<(usize, usize)> = Vec::new();
+        let mut next = 0;
+
+        self.events
+            .iter()
+            .map(|event| match event.kind {
+                Kind::Alloc => {
+                    next += 1;
+                    live.push((event.ptr, next));
+                    next
+                }
+                Kind::Free => match live.iter().position(|&(ptr, _)| ptr == event.ptr) {
+                    Some(i) => live.remove(i).1,
+                    None => 0,
+                },
+                Kind::Realloc { from, .. } => match live.iter_mut().find(|(ptr, _)| *ptr == from) {
+                    Some(entry) => {
+                        entry.0 = event.ptr;
+                        entry.1
+                    }
+                    None => 0,
+                },
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Timeline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let numbers = self.numbers();
+        let name = |number: usize| if number == 0 { "#?".to_string() } else { format!("#{}", number) };
+        // What the function printed goes between the events, where it printed it.
+        let prints = |f: &mut fmt::Formatter, before: usize| {
+            let mut lines = self.prints.iter().filter(|(events, _)| *events == before);
+            lines.try_for_each(|(_, line)| writeln!(f, "      | {}", line))
+        };
+
+        for (i, (event, &number)) in self.events.iter().zip(&numbers).enumerate() {
+            prints(f, i)?;
+            match event.kind {
+                Kind::Alloc => writeln!(f, "{:>4}. alloc   {} {:>6} bytes at {:#x}", i + 1, name(number), event.size, event.ptr)?,
+                Kind::Free => writeln!(f, "{:>4}. free    {} {:>6} bytes at {:#x}", i + 1, name(number), event.size, event.ptr)?,
+                Kind::Realloc { from, old_size } => writeln!(
+                    f,
+                    "{:>4}. realloc {} {:>6} bytes at {:#x}, was {} bytes at {:#x}",
+                    i + 1,
+                    name(number),
+                    event.size,
+                    event.ptr,
+                    old_size,
+                    from
+                )?,
+            }
+        }
+        prints(f, self.events.len())?;
+        if self.lost > 0 {
+            writeln!(f, "      ... and {} more events that didn't fit in the log", self.lost)?;
+        }
+
+        let allocs = self.events.iter().filter(|event| event.kind == Kind::Alloc).count();
+        let frees = self.events.iter().filter(|event| event.kind == Kind::Free).count();
+        let allocated: usize = self.events.iter().filter(|event| event.kind == Kind::Alloc).map(|event| event.size).sum();
+        let plural = |count: usize| if count == 1 { "" } else { "s" };
+        write!(f, "{} allocation{} ({} bytes), {} free{}: ", allocs, plural(allocs), allocated, frees, plural(frees))?;
+        match self.live_bytes() {
+            0 => writeln!(f, "every byte was given back before the function returned"),
+            live => writeln!(f, "{} bytes were still allocated when the function returned", live),
+        }
+    }
+}
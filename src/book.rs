@@ -0,0 +1,251 @@
+// Course Book ---
+// The chapters are written as `//` comments around the code they explain, which reads well in an editor but can't be
+// published as is. This turns each chapter module into a Markdown page, and all of them into an mdBook:
+// top-level comments become prose, `// Section ---` comments become headings, functions become code blocks,
+// and the `mod` of each example that doesn't compile is replaced by the example and the error it fails with.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::compile_fail::{self, Annotation};
+use crate::lesson::{self, Chapter};
+
+/// The part of a chapter under one `// Section ---` heading.
+#[derive(Debug, PartialEq)]
+pub struct Section {
+    /// `None` for what comes before the first heading.
+    pub heading: Option<String>,
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Block {
+    /// A paragraph, with the line breaks of the comments it was written in.
+    Prose(String),
+    Code(String),
+    /// An example that doesn't compile, without its `//~` annotations, and the errors they expect
+    /// on lines counted in `code`.
+    Fails { code: String, errors: Vec<Annotation> },
+}
+
+/// The sections of a chapter, in the order they are written.
+pub fn sections(chapter: &Chapter) -> Vec<Section> {
+    let mut parser = Parser { module: chapter.module, sections: vec![Section { heading: None, blocks: Vec::new() }], paragraph: Vec::new() };
+    let lines: Vec<&str> = chapter.source.lines().take_while(|line| !line.starts_with("pub const CHAPTER")).collect();
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if line.starts_with("fn ") || line.starts_with("pub fn ") {
+            let end = lesson::end_of_function(&lines, i);
+            let body = &lines[i + 1..end];
+            // `main` of the first two chapters is only there to hold their introduction, under the title of the chapter.
+            // Other functions can be all comments too, like `change` with the line that doesn't compile commented out.
+            let comments: Option<Vec<&str>> =
+                body.iter().map(|line| if line.trim().is_empty() { Some("") } else { line.trim_start().strip_prefix("//") }).collect();
+            if comments.is_some_and(|comments| comments.iter().any(|comment| heading(comment.trim()).is_some())) {
+                for line in body {
+                    parser.line(line.strip_prefix("    ").unwrap_or(line));
+                }
+            } else {
+                parser.code(&lines[i..=end].join("\n"));
+            }
+            i = end + 1;
+        } else if let Some(name) = line.strip_prefix("mod ").and_then(|rest| rest.strip_suffix(';')) {
+            parser.example(name);
+            i += 1;
+        } else {
+            parser.line(line);
+            i += 1;
+        }
+    }
+
+    parser.end_paragraph();
+    parser.sections.retain(|section| section.heading.is_some() || !section.blocks.is_empty());
+    parser.sections
+}
+
+struct Parser<'a> {
+    module: &'a str,
+    sections: Vec<Section>,
+    paragraph: Vec<String>,
+}
+
+impl Parser<'_> {
+    // A line outside of any function.
+    fn line(&mut self, line: &str) {
+        let comment = match line.strip_prefix("//") {
+            Some(comment) => comment.trim(),
+            // `use` and the attributes of the `mod`s that are replaced, nothing the reader needs.
+            None if line.starts_with("use ") || line.starts_with("#[") => return,
+            None if line.trim().is_empty() => return self.end_paragraph(),
+            None => return self.code(line),
+        };
+
+        match heading(comment) {
+            Some((_, false)) => {}
+            Some((title, true)) => {
+                self.end_paragraph();
+                self.sections.push(Section { heading: Some(title.to_string()), blocks: Vec::new() });
+            }
+            None if comment.is_empty() => self.end_paragraph(),
+            None => self.paragraph.push(comment.to_string()),
+        }
+    }
+
+    fn end_paragraph(&mut self) {
+        if !self.paragraph.is_empty() {
+            let text = self.paragraph.join("\n");
+            self.paragraph.clear();
+            self.push(Block::Prose(text));
+        }
+    }
+
+    // Code that follows other code with only blank lines in between goes in the same block.
+    fn code(&mut self, code: &str) {
+        self.end_paragraph();
+        let blocks = &mut self.sections.last_mut().expect("there is always a section").blocks;
+        match blocks.last_mut() {
+            Some(Block::Code(previous)) => {
+                previous.push_str("\n\n");
+                previous.push_str(code);
+            }
+            _ => blocks.push(Block::Code(code.to_string())),
+        }
+    }
+
+    fn example(&mut self, name: &str) {
+        match compile_fail::find(&format!("{}::{}", self.module, name)) {
+            Some(example) => {
+                let (code, errors) = strip_annotations(example.source);
+                self.push(Block::Fails { code, errors });
+            }
+            None => self.code(&format!("mod {};", name)),
+        }
+    }
+
+    fn push(&mut self, block: Block) {
+        self.end_paragraph();
+        self.sections.last_mut().expect("there is always a section").blocks.push(block);
+    }
+}
+
+/// The title of a `// Title ---` comment, without the `//`, and whether it is a section:
+/// the title of the chapter has a longer line.
+pub fn heading(comment: &str) -> Option<(&str, bool)> {
+    let title = comment.trim_end_matches('-');
+    let dashes = comment.len() - title.len();
+    if dashes < 3 || title.trim().is_empty() {
+        return None;
+    }
+    Some((title.trim(), dashes == 3))
+}
+
+/// The source of an example that doesn't compile, as someone reading about it should see it,
+/// with its annotations moved to the lines they have there.
+pub fn strip_annotations(source: &str) -> (String, Vec<Annotation>) {
+    let mut code = Vec::new();
+    // The line of `code` each line of `source` ended up on.
+    let mut moved_to = Vec::new();
+    for line in source.lines() {
+        if !line.trim_start().starts_with("//~") {
+            code.push(match line.find("//~") {
+                Some(i) => line[..i].trim_end(),
+                None => line,
+            });
+        }
+        moved_to.push(code.len());
+    }
+
+    let annotations = compile_fail::annotations(source)
+        .into_iter()
+        .map(|annotation| Annotation { line: moved_to[annotation.line - 1], ..annotation })
+        .collect();
+    (code.join("\n"), annotations)
+}
+
+// Markdown ---
+
+/// The anchor mdBook and most Markdown renderers give a heading: `ways-variables-and-data-interact-move`.
+pub fn anchor(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The page of a chapter.
+pub fn to_markdown(chapter: &Chapter) -> String {
+    let mut page = format!("# {}\n", chapter.title);
+
+    for section in sections(chapter) {
+        if let Some(heading) = &section.heading {
+            let _ = write!(page, "\n## {}\n", heading);
+        }
+        for block in &section.blocks {
+            match block {
+                Block::Prose(text) => {
+                    let _ = write!(page, "\n{}\n", text);
+                }
+                Block::Code(code) => {
+                    let _ = write!(page, "\n```rust\n{}\n```\n", code);
+                }
+                Block::Fails { code, errors } => {
+                    let _ = write!(page, "\n```rust,compile_fail\n{}\n```\n", code);
+                    let _ = write!(page, "\nThis doesn't compile:\n\n```text\n");
+                    for error in errors {
+                        let _ = writeln!(page, "error[{}]: {}", error.code, error.message);
+                    }
+                    page.push_str("```\n");
+                }
+            }
+        }
+    }
+    page
+}
+
+/// The name of the page of a chapter in the book.
+pub fn file_name(chapter: &Chapter) -> String {
+    format!("{}.md", chapter.module)
+}
+
+/// The introduction of the book: a table of contents with every section of every chapter.
+pub fn contents() -> String {
+    let mut page = String::from("# Understanding Ownership\n\nNotes on ownership, borrowing and slices, with the examples they explain.\n");
+    for chapter in lesson::CHAPTERS.iter() {
+        let _ = write!(page, "\n- [{}]({})\n", chapter.title, file_name(chapter));
+        for heading in sections(chapter).iter().filter_map(|section| section.heading.as_ref()) {
+            let _ = writeln!(page, "    - [{}]({}#{})", heading, file_name(chapter), anchor(heading));
+        }
+    }
+    page
+}
+
+/// Writes an mdBook to `dir`, `mdbook build` can turn it into a website. Returns the files it wrote.
+pub fn write(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let src = dir.join("src");
+    fs::create_dir_all(&src)?;
+
+    let mut summary = String::from("# Summary\n\n[Introduction](README.md)\n\n");
+    let mut files = vec![
+        (dir.join("book.toml"), "[book]\ntitle = \"Understanding Ownership\"\nsrc = \"src\"\n".to_string()),
+        (src.join("README.md"), contents()),
+    ];
+    for chapter in lesson::CHAPTERS.iter() {
+        let _ = writeln!(summary, "- [{}]({})", chapter.title, file_name(chapter));
+        files.push((src.join(file_name(chapter)), to_markdown(chapter)));
+    }
+    files.push((src.join("SUMMARY.md"), summary));
+
+    for (path, contents) in &files {
+        fs::write(path, contents)?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
@@ -0,0 +1,96 @@
+// Checked String Slices ---
+// `&s[0..5]` panics if a bound is past the end of the string, if start is after end, or if a bound falls in the middle
+// of a multibyte character: "String slice range indices must occur at valid UTF-8 character boundaries".
+// `checked_slice` takes the same ranges and says which of those went wrong instead, and `snapped_slice` moves
+// the bounds to char boundaries so it always has a slice to give back.
+
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SliceError {
+    /// A bound past the end of the string, which is `len` bytes long.
+    OutOfBounds { index: usize, len: usize },
+    /// A bound inside a character that takes more than one byte.
+    NotCharBoundary { index: usize },
+    /// A range that starts after it ends.
+    InvertedRange { start: usize, end: usize },
+}
+
+impl fmt::Display for SliceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SliceError::OutOfBounds { index, len } => write!(f, "byte index {} is out of bounds of a {} byte string", index, len),
+            SliceError::NotCharBoundary { index } => write!(f, "byte index {} is not a char boundary", index),
+            SliceError::InvertedRange { start, end } => write!(f, "range starts at {} but ends at {}", start, end),
+        }
+    }
+}
+
+impl std::error::Error for SliceError {}
+
+/// `&s[range]`, or why it would panic.
+///
+/// A bound past the end is reported first, then an inverted range, then a bound that isn't a char boundary.
+pub fn checked_slice(s: &str, range: impl RangeBounds<usize>) -> Result<&str, SliceError> {
+    let (start, end) = bounds(s, range);
+
+    for index in [end, start] {
+        if index > s.len() {
+            return Err(SliceError::OutOfBounds { index, len: s.len() });
+        }
+    }
+    if start > end {
+        return Err(SliceError::InvertedRange { start, end });
+    }
+    for index in [start, end] {
+        if !s.is_char_boundary(index) {
+            return Err(SliceError::NotCharBoundary { index });
+        }
+    }
+
+    Ok(&s[start..end])
+}
+
+/// The slice of `s` that covers `range` with whole characters: a start inside a character moves back to where it starts,
+/// an end inside one moves forward to where it ends.
+///
+/// Bounds past the end stop at the end, and an inverted range gives an empty slice at its snapped start.
+pub fn snapped_slice(s: &str, range: impl RangeBounds<usize>) -> &str {
+    let (start, end) = bounds(s, range);
+    let start = floor_char_boundary(s, start);
+    let end = ceil_char_boundary(s, end).max(start);
+    &s[start..end]
+}
+
+/// The closest char boundary at or before `index`, or the length of `s` if `index` is past it.
+pub fn floor_char_boundary(s: &str, index: usize) -> usize {
+    if index >= s.len() {
+        return s.len();
+    }
+    // A char is at most 4 bytes long, and 0 is always a boundary.
+    (0..=index).rev().find(|&i| s.is_char_boundary(i)).unwrap_or(0)
+}
+
+/// The closest char boundary at or after `index`, or the length of `s` if `index` is past it.
+pub fn ceil_char_boundary(s: &str, index: usize) -> usize {
+    if index >= s.len() {
+        return s.len();
+    }
+    (index..s.len()).find(|&i| s.is_char_boundary(i)).unwrap_or(s.len())
+}
+
+// The start and end byte indexes of any range, `..` and `3..=5` included.
+fn bounds(s: &str, range: impl RangeBounds<usize>) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.saturating_add(1),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => s.len(),
+    };
+    (start, end)
+}
@@ -0,0 +1,279 @@
+// Compile-Fail Examples ---
+// Some examples exist to show a compile error, so they can't live in the chapter modules without breaking the build.
+// Each one is a standalone file under tests/compile-fail, and tests/compile_fail.rs checks that rustc still rejects it
+// with the error codes of its `//~ ERROR` annotations. For the reader, each one also quotes its error in a comment,
+// which `Example::check` compares with what rustc says now.
+// The chapter modules include them back when the `show-errors` feature is on, to reproduce the errors with cargo.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::rustc::{self, Diagnostic};
+
+/// An example that rustc must reject.
+pub struct Example {
+    /// The id of the lesson it belongs to, as in `lesson::find`.
+    pub lesson: &'static str,
+    /// The path of the example, relative to the root of the crate.
+    pub path: &'static str,
+    pub source: &'static str,
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        lesson: "ownership::error",
+        path: "tests/compile-fail/ownership/error.rs",
+        source: include_str!("../tests/compile-fail/ownership/error.rs"),
+    },
+    Example {
+        lesson: "references_and_borrowing::fail",
+        path: "tests/compile-fail/references_and_borrowing/fail.rs",
+        source: include_str!("../tests/compile-fail/references_and_borrowing/fail.rs"),
+    },
+    Example {
+        lesson: "references_and_borrowing::dsa",
+        path: "tests/compile-fail/references_and_borrowing/dsa.rs",
+        source: include_str!("../tests/compile-fail/references_and_borrowing/dsa.rs"),
+    },
+    Example {
+        lesson: "references_and_borrowing::dangle",
+        path: "tests/compile-fail/references_and_borrowing/dangle.rs",
+        source: include_str!("../tests/compile-fail/references_and_borrowing/dangle.rs"),
+    },
+    Example {
+        lesson: "slice_type::main",
+        path: "tests/compile-fail/slice_type/main.rs",
+        source: include_str!("../tests/compile-fail/slice_type/main.rs"),
+    },
+    Example {
+        lesson: "slice_type::main_same",
+        path: "tests/compile-fail/slice_type/main_same.rs",
+        source: include_str!("../tests/compile-fail/slice_type/main_same.rs"),
+    },
+];
+
+/// Finds the example of a lesson.
+pub fn find(lesson: &str) -> Option<&'static Example> {
+    EXAMPLES.iter().find(|example| example.lesson == lesson)
+}
+
+impl Example {
+    /// The error codes of the annotations of the example, like `E0382` in `//~ ERROR E0382: ...`,
+    /// sorted and without duplicates.
+    pub fn expected_codes(&self) -> Vec<&'static str> {
+        let mut codes: Vec<&str> = self.source.lines().filter_map(annotation).map(|(_, code, _)| code).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        codes
+    }
+}
+
+/// The error codes found in `text`, written as `error[E0000]`, sorted and without duplicates.
+pub fn error_codes(text: &str) -> Vec<&str> {
+    let mut codes: Vec<&str> = text
+        .match_indices("error[E")
+        .filter_map(|(i, _)| {
+            let code = text.get(i + "error[".len()..i + "error[E0000".len())?;
+            let closed = text[i + "error[E0000".len()..].starts_with(']');
+            let digits = code[1..].bytes().all(|b| b.is_ascii_digit());
+            if closed && digits {
+                Some(code)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    codes.sort_unstable();
+    codes.dedup();
+    codes
+}
+
+// Expected Diagnostics ---
+// The prose next to an example quotes what rustc printed when it was written, and rustc's wording changes over time.
+// So each example also carries annotations that say exactly which error is expected, and where:
+//
+//     let r2 = &mut s; //~ ERROR E0499: cannot borrow `s` as mutable
+//
+// An annotation applies to the line it is written on, or to the lines above it with one `^` per line (`//~^`, `//~^^`).
+// The text after the code only has to be part of the message rustc prints.
+
+/// An expected error, read from a `//~ ERROR` comment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    /// The line of the primary span, counting from 1.
+    pub line: usize,
+    pub code: String,
+    pub message: String,
+}
+
+/// The `//~ ERROR` annotations of a source file.
+pub fn annotations(source: &str) -> Vec<Annotation> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let (carets, code, message) = annotation(line)?;
+            Some(Annotation { line: (i + 1).checked_sub(carets)?, code: code.to_string(), message: message.to_string() })
+        })
+        .collect()
+}
+
+// The annotation on a line: how many lines up it points, its code and its message.
+fn annotation(line: &str) -> Option<(usize, &str, &str)> {
+    let rest = &line[line.find("//~")? + "//~".len()..];
+    let carets = rest.len() - rest.trim_start_matches('^').len();
+    let rest = rest[carets..].trim_start().strip_prefix("ERROR")?.trim_start();
+    let (code, message) = rest.split_once(':').unwrap_or((rest, ""));
+    Some((carets, code.trim(), message.trim()))
+}
+
+/// Something in an example that doesn't match what rustc says about it.
+#[derive(Debug)]
+pub enum Problem {
+    /// An annotated error that rustc didn't report, with the errors it reported instead.
+    Missing { expected: Annotation, actual: Vec<Diagnostic> },
+    /// An error that rustc reported but nothing annotates.
+    Unexpected(Diagnostic),
+    /// A quoted `error[E0000]: ...` comment that no longer reads like rustc's message.
+    StaleExplanation { line: usize, written: String, actual: Vec<String> },
+}
+
+/// The outcome of checking one example against rustc.
+#[derive(Debug)]
+pub struct Report {
+    pub lesson: &'static str,
+    pub path: &'static str,
+    pub problems: Vec<Problem>,
+}
+
+impl Example {
+    /// Compiles the example with the local rustc, in `dir`, and compares what it reports with the annotations
+    /// and the quoted errors in the comments.
+    pub fn check(&self, dir: &Path) -> io::Result<Report> {
+        let name = self.lesson.replace("::", "_");
+        let diagnostics = rustc::check(self.source, &name, dir)?;
+        let errors: Vec<&Diagnostic> = diagnostics.iter().filter(|d| d.level == "error" && d.code.is_some()).collect();
+
+        let mut problems = Vec::new();
+        let expected = annotations(self.source);
+
+        for annotation in &expected {
+            let found = errors.iter().any(|error| {
+                error.code.as_deref() == Some(annotation.code.as_str())
+                    && error.line == Some(annotation.line)
+                    && error.message.contains(&annotation.message)
+            });
+            if !found {
+                problems.push(Problem::Missing { expected: annotation.clone(), actual: errors.iter().map(|&e| e.clone()).collect() });
+            }
+        }
+
+        for error in &errors {
+            let annotated = expected
+                .iter()
+                .any(|annotation| error.code.as_deref() == Some(annotation.code.as_str()) && error.line == Some(annotation.line));
+            if !annotated {
+                problems.push(Problem::Unexpected((*error).clone()));
+            }
+        }
+
+        for (line, code, written) in quoted_errors(self.source) {
+            let same_code: Vec<&&Diagnostic> = errors.iter().filter(|error| error.code.as_deref() == Some(code)).collect();
+            let (message, help) = match written.split_once(", help: ") {
+                Some((message, help)) => (message, Some(help)),
+                None => (written, None),
+            };
+
+            let current = same_code.iter().any(|error| {
+                error.message == message
+                    && help.is_none_or(|help| {
+                        // Ignore the suggested code after the help text, e.g. "...lifetime: `&'static`".
+                        let help = help.split(": `").next().unwrap_or(help);
+                        error.children.iter().any(|child| child.contains(help))
+                    })
+            });
+            if !current {
+                problems.push(Problem::StaleExplanation {
+                    line,
+                    written: written.to_string(),
+                    actual: same_code
+                        .iter()
+                        .map(|error| {
+                            let mut text = error.message.clone();
+                            for child in error.children.iter().filter(|child| child.starts_with("help")) {
+                                text.push_str(", ");
+                                text.push_str(child);
+                            }
+                            text
+                        })
+                        .collect(),
+                });
+            }
+        }
+
+        Ok(Report { lesson: self.lesson, path: self.path, problems })
+    }
+}
+
+// The `error[E0000]: message` quotes in the comments of `source`, with their line.
+fn quoted_errors(source: &str) -> Vec<(usize, &str, &str)> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let comment = &line[line.find("//")?..];
+            if comment.starts_with("//~") {
+                return None;
+            }
+            let start = comment.find("error[E")?;
+            let code = comment.get(start + "error[".len()..start + "error[E0000".len())?;
+            let written = comment[start + "error[E0000".len()..].strip_prefix("]:")?.trim();
+            Some((i + 1, code, written))
+        })
+        .collect()
+}
+
+impl Report {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_ok() {
+            return write!(f, "ok    {} ({})", self.lesson, self.path);
+        }
+
+        write!(f, "stale {} ({})", self.lesson, self.path)?;
+        for problem in &self.problems {
+            match problem {
+                Problem::Missing { expected, actual } => {
+                    write!(f, "\n    line {}: expected {}: {}", expected.line, expected.code, expected.message)?;
+                    if actual.is_empty() {
+                        write!(f, "\n        but rustc reported no error")?;
+                    }
+                    for error in actual {
+                        let code = error.code.as_deref().unwrap_or("");
+                        let line = error.line.map_or(String::from("?"), |line| line.to_string());
+                        write!(f, "\n        rustc: line {}: {}: {}", line, code, error.message)?;
+                    }
+                }
+                Problem::Unexpected(error) => {
+                    let code = error.code.as_deref().unwrap_or("");
+                    let line = error.line.map_or(String::from("?"), |line| line.to_string());
+                    write!(f, "\n    line {}: unexpected {}: {}", line, code, error.message)?;
+                }
+                Problem::StaleExplanation { line, written, actual } => {
+                    write!(f, "\n    line {}: the comment says: {}", line, written)?;
+                    for text in actual {
+                        write!(f, "\n        rustc now says: {}", text)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
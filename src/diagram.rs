@@ -0,0 +1,547 @@
+// Memory Diagrams ---
+// The prose describes a String as a pointer, a length and a capacity on the stack, plus a buffer on the heap.
+// A snapshot records exactly that, so it can be drawn. The simulator takes one after each line of a lesson's source,
+// with made-up addresses; `Diagram::live` runs the lesson for real and records its live values, with their real addresses.
+
+use std::fmt;
+use std::fmt::Write;
+
+use crate::lesson;
+use crate::simulator;
+
+// Buffers with a larger capacity only show this many bytes.
+const MAX_CELLS: usize = 32;
+
+/// The stack and the heap after one line of an example.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    /// The line of code the snapshot was taken after.
+    pub code: String,
+    pub frames: Vec<Frame>,
+    /// Every buffer a slot points into, in the order they were first seen.
+    pub heap: Vec<Buffer>,
+}
+
+/// The variables of one function call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub function: String,
+    pub slots: Vec<Slot>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slot {
+    pub name: String,
+    pub value: SlotValue,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SlotValue {
+    String { ptr: usize, len: usize, capacity: usize },
+    /// A `&str`, which has no capacity: it only borrows part of a buffer.
+    Str { ptr: usize, len: usize },
+    /// A variable whose value was moved out, that is only there to show it is no longer valid.
+    Moved { into: String },
+    /// A reference to another variable on the stack, like `&s1`.
+    Ref { to: String, mutable: bool },
+    /// A value stored entirely on the stack, like an integer.
+    Plain(String),
+}
+
+/// A heap allocation owned by a `String`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Buffer {
+    pub ptr: usize,
+    pub capacity: usize,
+    /// The bytes in use, `len` of them.
+    pub bytes: Vec<u8>,
+}
+
+impl Buffer {
+    /// How many bytes the diagrams show: the whole capacity, unless it is large.
+    pub fn cells(&self) -> usize {
+        self.capacity.min(MAX_CELLS)
+    }
+
+    /// The byte at `i` as it is drawn, the character if it is printable ASCII and its hex value otherwise.
+    /// `None` for capacity that is not in use yet.
+    pub fn cell(&self, i: usize) -> Option<String> {
+        self.bytes.get(i).map(|&byte| match byte {
+            b' ' => " ".to_string(),
+            byte if byte.is_ascii_graphic() => (byte as char).to_string(),
+            byte => format!("{:02x}", byte),
+        })
+    }
+}
+
+impl Snapshot {
+    pub fn new(code: &str) -> Snapshot {
+        Snapshot { code: code.to_string(), frames: Vec::new(), heap: Vec::new() }
+    }
+
+    /// Starts the frame of a function call, the slots that follow go in it.
+    pub fn frame(mut self, function: &str) -> Snapshot {
+        self.frames.push(Frame { function: function.to_string(), slots: Vec::new() });
+        self
+    }
+
+    /// Records a `String` and the heap buffer it owns.
+    pub fn string(mut self, name: &str, s: &String) -> Snapshot {
+        let ptr = s.as_ptr() as usize;
+        // An empty `String::new()` doesn't allocate, its pointer points at nothing.
+        if s.capacity() > 0 && !self.heap.iter().any(|buffer| buffer.ptr == ptr) {
+            self.heap.push(Buffer { ptr, capacity: s.capacity(), bytes: s.as_bytes().to_vec() });
+        }
+        self.slot(name, SlotValue::String { ptr, len: s.len(), capacity: s.capacity() })
+    }
+
+    /// Records a string slice. Record the `String` it borrows from first, so its buffer is known.
+    pub fn str(self, name: &str, s: &str) -> Snapshot {
+        self.slot(name, SlotValue::Str { ptr: s.as_ptr() as usize, len: s.len() })
+    }
+
+    /// Records a variable whose value was moved into `into`.
+    pub fn moved(self, name: &str, into: &str) -> Snapshot {
+        self.slot(name, SlotValue::Moved { into: into.to_string() })
+    }
+
+    fn slot(mut self, name: &str, value: SlotValue) -> Snapshot {
+        if self.frames.is_empty() {
+            self = self.frame("main");
+        }
+        let frame = self.frames.last_mut().expect("there is a frame");
+        frame.slots.push(Slot { name: name.to_string(), value });
+        self
+    }
+
+    /// The buffer `ptr` points into, by index in `heap`, and the offset into it.
+    pub fn buffer_of(&self, ptr: usize) -> Option<(usize, usize)> {
+        self.heap
+            .iter()
+            .position(|buffer| buffer.ptr <= ptr && ptr <= buffer.ptr + buffer.capacity)
+            .map(|index| (index, ptr - self.heap[index].ptr))
+    }
+
+    /// Every slot pointing into the buffer `index`, with the offset it points at.
+    pub fn pointers_to(&self, index: usize) -> Vec<(&Slot, usize)> {
+        self.frames
+            .iter()
+            .flat_map(|frame| &frame.slots)
+            .filter_map(|slot| match slot.value {
+                SlotValue::String { ptr, .. } | SlotValue::Str { ptr, .. } => match self.buffer_of(ptr) {
+                    Some((buffer, offset)) if buffer == index => Some((slot, offset)),
+                    _ => None,
+                },
+                SlotValue::Moved { .. } | SlotValue::Ref { .. } | SlotValue::Plain(_) => None,
+            })
+            .collect()
+    }
+}
+
+// Text Rendering ---
+
+/// Draws the stack on the left and the heap on the right, with an arrow from each pointer to the buffer it points into.
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "after `{}`", self.code)?;
+        writeln!(f)?;
+
+        let stack = self.stack_lines();
+        let heap = self.heap_lines();
+        let width = stack.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+
+        for i in 0..stack.len().max(heap.len()) {
+            let left = stack.get(i).map_or("", String::as_str);
+            let right = heap.get(i).map_or("", String::as_str);
+            let line = format!("{:<width$}    {}", left, right, width = width);
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl Snapshot {
+    fn stack_lines(&self) -> Vec<String> {
+        let mut lines = vec!["stack".to_string()];
+
+        for frame in &self.frames {
+            let rows: Vec<(String, Vec<(String, String)>)> =
+                frame.slots.iter().map(|slot| (slot.name.clone(), self.slot_rows(slot))).collect();
+            let names = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max(frame.function.len());
+            let values = rows.iter().flat_map(|(_, rows)| rows).map(|(text, _)| text.len()).max().unwrap_or(0);
+            let border = format!("+-{}-+-{}-+", "-".repeat(names), "-".repeat(values));
+
+            lines.push(format!("{} (frame)", frame.function));
+            lines.push(border.clone());
+            for (name, rows) in rows {
+                for (i, (text, arrow)) in rows.into_iter().enumerate() {
+                    let name = if i == 0 { name.as_str() } else { "" };
+                    let line = format!("| {:<names$} | {:<values$} | {}", name, text, arrow, names = names, values = values);
+                    lines.push(line.trim_end().to_string());
+                }
+                lines.push(border.clone());
+            }
+        }
+        lines
+    }
+
+    // The rows of a slot in the stack box, each with what to draw after the box.
+    fn slot_rows(&self, slot: &Slot) -> Vec<(String, String)> {
+        let arrow = |ptr: usize| match self.buffer_of(ptr) {
+            Some((index, 0)) => format!("--> #{}", index + 1),
+            Some((index, offset)) => format!("--> #{} + {}", index + 1, offset),
+            None => "--> static memory".to_string(),
+        };
+
+        match &slot.value {
+            SlotValue::String { ptr, len, capacity: 0 } => vec![
+                (format!("ptr      {:#x}", ptr), "(no heap buffer yet)".to_string()),
+                (format!("len      {}", len), String::new()),
+                ("capacity 0".to_string(), String::new()),
+            ],
+            SlotValue::String { ptr, len, capacity } => vec![
+                (format!("ptr      {:#x}", ptr), arrow(*ptr)),
+                (format!("len      {}", len), String::new()),
+                (format!("capacity {}", capacity), String::new()),
+            ],
+            SlotValue::Str { ptr, len } => vec![
+                (format!("ptr      {:#x}", ptr), arrow(*ptr)),
+                (format!("len      {}", len), String::new()),
+            ],
+            SlotValue::Moved { into } => vec![(format!("moved into {}, no longer valid", into), String::new())],
+            SlotValue::Ref { to, mutable } => {
+                let kind = if *mutable { "&mut " } else { "&" };
+                vec![(format!("{}{}", kind, to), format!("--> {}", to))]
+            }
+            SlotValue::Plain(value) => vec![(value.clone(), String::new())],
+        }
+    }
+
+    fn heap_lines(&self) -> Vec<String> {
+        let mut lines = vec!["heap".to_string()];
+
+        for (index, buffer) in self.heap.iter().enumerate() {
+            if index > 0 {
+                lines.push(String::new());
+            }
+            let cells = buffer.cells();
+            let more = if buffer.capacity > cells { " ..." } else { "" };
+            let border = format!("{}+{}", "+---".repeat(cells), more);
+            let content: String = (0..cells)
+                .map(|i| format!("|{:^3}", buffer.cell(i).unwrap_or_else(|| ".".to_string())))
+                .collect();
+
+            lines.push(format!("#{} at {:#x}, capacity {}", index + 1, buffer.ptr, buffer.capacity));
+            lines.push(border.clone());
+            lines.push(format!("{}|{}", content, more));
+            lines.push(border);
+
+            // One line per place the buffer is pointed at, with the names of the slots pointing there.
+            let mut pointers = self.pointers_to(index);
+            pointers.sort_by_key(|&(_, offset)| offset);
+            let mut offsets: Vec<usize> = pointers.iter().map(|&(_, offset)| offset).collect();
+            offsets.dedup();
+            for offset in offsets {
+                let names: Vec<&str> = pointers.iter().filter(|&&(_, o)| o == offset).map(|(slot, _)| slot.name.as_str()).collect();
+                lines.push(format!("{}^ {}", " ".repeat(offset.min(cells) * 4 + 2), names.join(", ")));
+            }
+        }
+
+        if self.heap.is_empty() {
+            lines.push("(empty)".to_string());
+        }
+        lines
+    }
+}
+
+// SVG Rendering ---
+// The same drawing as the text one, as a standalone image for slides.
+
+// Sizes in pixels, for a 14px monospace font.
+const CHAR_WIDTH: f64 = 8.4;
+const ROW_HEIGHT: f64 = 22.0;
+const CELL_WIDTH: f64 = 26.0;
+const MARGIN: f64 = 20.0;
+
+impl Snapshot {
+    /// A standalone SVG document. `id` is prefixed to the ids it defines, so that several SVGs can share a page:
+    /// give each one its own, like `ownership-bye-2`.
+    pub fn to_svg(&self, id: &str) -> String {
+        let mut body = String::new();
+        let mut arrows = Vec::new();
+
+        // The stack, frame after frame, one box per slot.
+        let mut y = MARGIN + ROW_HEIGHT * 2.0;
+        text(&mut body, MARGIN, y - 6.0, "stack", "title");
+        let mut stack_right: f64 = MARGIN;
+        for frame in &self.frames {
+            y += ROW_HEIGHT;
+            text(&mut body, MARGIN, y - 6.0, &format!("{} (frame)", frame.function), "frame");
+
+            let rows: Vec<Vec<(String, String)>> = frame.slots.iter().map(|slot| self.slot_rows(slot)).collect();
+            let names = frame.slots.iter().map(|slot| slot.name.len()).max().unwrap_or(0).max(4);
+            let values = rows.iter().flatten().map(|(text, _)| text.len()).max().unwrap_or(0);
+            let name_width = names as f64 * CHAR_WIDTH + 16.0;
+            let value_width = values as f64 * CHAR_WIDTH + 16.0;
+            stack_right = stack_right.max(MARGIN + name_width + value_width);
+
+            for (slot, rows) in frame.slots.iter().zip(rows) {
+                let height = rows.len() as f64 * ROW_HEIGHT;
+                let class = if matches!(slot.value, SlotValue::Moved { .. }) { "moved" } else { "slot" };
+                rect(&mut body, MARGIN, y, name_width, height, class);
+                rect(&mut body, MARGIN + name_width, y, value_width, height, class);
+                text(&mut body, MARGIN + 8.0, y + 15.0, &slot.name, "code");
+                for (i, (line, _)) in rows.iter().enumerate() {
+                    text(&mut body, MARGIN + name_width + 8.0, y + 15.0 + i as f64 * ROW_HEIGHT, line, "code");
+                }
+                if let SlotValue::String { ptr, capacity: 1.., .. } | SlotValue::Str { ptr, .. } = slot.value {
+                    arrows.push((MARGIN + name_width + value_width, y + ROW_HEIGHT / 2.0, ptr));
+                }
+                y += height;
+            }
+        }
+        let stack_bottom = y;
+
+        // The heap on the right, one row of cells per buffer.
+        let heap_left = stack_right + 120.0;
+        let mut y = MARGIN + ROW_HEIGHT * 2.0;
+        text(&mut body, heap_left, y - 6.0, "heap", "title");
+        let mut cell_tops = Vec::new();
+        let mut heap_right = heap_left + 200.0;
+        for (index, buffer) in self.heap.iter().enumerate() {
+            y += ROW_HEIGHT;
+            text(&mut body, heap_left, y - 6.0, &format!("#{} at {:#x}, capacity {}", index + 1, buffer.ptr, buffer.capacity), "frame");
+            for i in 0..buffer.cells() {
+                let x = heap_left + i as f64 * CELL_WIDTH;
+                let cell = buffer.cell(i);
+                rect(&mut body, x, y, CELL_WIDTH, ROW_HEIGHT + 6.0, if cell.is_some() { "cell" } else { "spare" });
+                if let Some(cell) = cell {
+                    text(&mut body, x + CELL_WIDTH / 2.0, y + 19.0, &cell, "byte");
+                }
+            }
+            heap_right = heap_right.max(heap_left + buffer.cells() as f64 * CELL_WIDTH);
+            cell_tops.push(y);
+            y += ROW_HEIGHT * 2.0;
+        }
+        if self.heap.is_empty() {
+            text(&mut body, heap_left, y + 15.0, "(empty)", "frame");
+        }
+        let heap_bottom = y;
+
+        for (x, y, ptr) in arrows {
+            let (to_x, to_y) = match self.buffer_of(ptr) {
+                Some((index, offset)) => {
+                    let offset = offset.min(self.heap[index].cells());
+                    (heap_left + offset as f64 * CELL_WIDTH + 4.0, cell_tops[index] + ROW_HEIGHT / 2.0)
+                }
+                None => (heap_left - 40.0, y),
+            };
+            let _ = writeln!(
+                body,
+                r#"  <path class="arrow" d="M {:.1} {:.1} C {:.1} {:.1}, {:.1} {:.1}, {:.1} {:.1}" marker-end="url(#{}-head)"/>"#,
+                x,
+                y,
+                x + 60.0,
+                y,
+                to_x - 60.0,
+                to_y,
+                to_x,
+                to_y,
+                id
+            );
+        }
+
+        let width = heap_right + MARGIN;
+        let height = stack_bottom.max(heap_bottom) + MARGIN;
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.0} {h:.0}">"#,
+            w = width,
+            h = height
+        );
+        svg.push_str(&SVG_STYLE.replace("{id}", id));
+        let _ = writeln!(svg, r#"  <rect width="100%" height="100%" fill="white"/>"#);
+        text(&mut svg, MARGIN, MARGIN + 4.0, &format!("after `{}`", self.code), "title");
+        svg.push_str(&body);
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+const SVG_STYLE: &str = r##"  <defs>
+    <marker id="{id}-head" viewBox="0 0 10 10" refX="9" refY="5" markerWidth="8" markerHeight="8" orient="auto">
+      <path d="M 0 0 L 10 5 L 0 10 z" fill="#333"/>
+    </marker>
+    <style>
+      text { font-family: monospace; font-size: 14px; fill: #222; }
+      .title { font-weight: bold; }
+      .frame { fill: #666; }
+      .byte { text-anchor: middle; }
+      .slot, .cell { fill: #fff; stroke: #333; }
+      .moved { fill: #eee; stroke: #999; stroke-dasharray: 4 2; }
+      .spare { fill: #f4f4f4; stroke: #bbb; }
+      .arrow { fill: none; stroke: #333; stroke-width: 1.5; }
+    </style>
+  </defs>
+"##;
+
+fn rect(svg: &mut String, x: f64, y: f64, width: f64, height: f64, class: &str) {
+    let _ = writeln!(svg, r#"  <rect class="{}" x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}"/>"#, class, x, y, width, height);
+}
+
+fn text(svg: &mut String, x: f64, y: f64, content: &str, class: &str) {
+    let _ = writeln!(svg, r#"  <text class="{}" x="{:.1}" y="{:.1}" xml:space="preserve">{}</text>"#, class, x, y, escape_xml(content));
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Graphviz Rendering ---
+// A DOT graph with a table per frame and per buffer, laid out by `dot -Tsvg` or `dot -Tpng`.
+
+impl Snapshot {
+    /// A DOT graph, with an edge from each pointer to the byte it points at.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        let _ = writeln!(dot, "digraph snapshot {{");
+        let _ = writeln!(dot, "    rankdir=LR;");
+        let _ = writeln!(dot, "    labelloc=t;");
+        let _ = writeln!(dot, "    label=\"after `{}`\";", self.code.replace('\\', "\\\\").replace('"', "\\\""));
+        let _ = writeln!(dot, "    node [shape=plaintext, fontname=\"monospace\"];");
+        let _ = writeln!(dot, "    edge [tailclip=false];");
+
+        let _ = writeln!(dot, "    subgraph cluster_stack {{");
+        let _ = writeln!(dot, "        label=\"stack\";");
+        for (f, frame) in self.frames.iter().enumerate() {
+            let mut table = format!(
+                r#"<table border="0" cellborder="1" cellspacing="0"><tr><td colspan="2"><b>{} (frame)</b></td></tr>"#,
+                escape_xml(&frame.function)
+            );
+            for (s, slot) in frame.slots.iter().enumerate() {
+                let rows = self.slot_rows(slot);
+                let style = if matches!(slot.value, SlotValue::Moved { .. }) { r##" bgcolor="#eeeeee""## } else { "" };
+                for (i, (line, _)) in rows.iter().enumerate() {
+                    table.push_str("<tr>");
+                    if i == 0 {
+                        let _ = write!(table, r#"<td rowspan="{}"{}>{}</td>"#, rows.len(), style, escape_xml(&slot.name));
+                    }
+                    let port = if i == 0 { format!(r#" port="s{}""#, s) } else { String::new() };
+                    let _ = write!(table, r#"<td align="left"{}{}>{}</td>"#, port, style, escape_xml(line));
+                    table.push_str("</tr>");
+                }
+            }
+            table.push_str("</table>");
+            let _ = writeln!(dot, "        frame{} [label=<{}>];", f, table);
+        }
+        let _ = writeln!(dot, "    }}");
+
+        let _ = writeln!(dot, "    subgraph cluster_heap {{");
+        let _ = writeln!(dot, "        label=\"heap\";");
+        for (index, buffer) in self.heap.iter().enumerate() {
+            let mut table = format!(
+                r#"<table border="0" cellborder="1" cellspacing="0"><tr><td colspan="{}">#{} at {:#x}, capacity {}</td></tr><tr>"#,
+                buffer.cells().max(1),
+                index + 1,
+                buffer.ptr,
+                buffer.capacity
+            );
+            for i in 0..buffer.cells() {
+                match buffer.cell(i) {
+                    Some(cell) => {
+                        let _ = write!(table, r#"<td port="c{}">{}</td>"#, i, escape_xml(&cell));
+                    }
+                    None => {
+                        let _ = write!(table, r##"<td port="c{}" bgcolor="#f4f4f4"> </td>"##, i);
+                    }
+                }
+            }
+            table.push_str("</tr></table>");
+            let _ = writeln!(dot, "        buffer{} [label=<{}>];", index, table);
+        }
+        let _ = writeln!(dot, "    }}");
+
+        for (f, frame) in self.frames.iter().enumerate() {
+            for (s, slot) in frame.slots.iter().enumerate() {
+                if let SlotValue::String { ptr, .. } | SlotValue::Str { ptr, .. } = slot.value {
+                    if let Some((index, offset)) = self.buffer_of(ptr) {
+                        let offset = offset.min(self.heap[index].cells().saturating_sub(1));
+                        let _ = writeln!(dot, "    frame{}:s{}:e -> buffer{}:c{}:n;", f, s, index, offset);
+                    }
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+// Examples ---
+// The lessons drawn after each line. They are simulated from their source, so the addresses are made up,
+// but the steps are the lines of the chapter as it is written.
+
+pub struct Diagram {
+    /// The lesson the diagram is drawn from, `module::function`.
+    pub lesson: &'static str,
+    /// The lesson again, run for real with a snapshot of its live values after each line.
+    pub live: fn() -> Vec<Snapshot>,
+}
+
+pub const DIAGRAMS: &[Diagram] = &[
+    // The move copies the pointer, length and capacity: s2 points at the very buffer s1 pointed at.
+    Diagram { lesson: "ownership::bye", live: bye },
+    // The clone copies the heap data too: s2 gets a buffer of its own.
+    Diagram { lesson: "ownership::cloning", live: cloning },
+    // Slices point into the buffer of the String they borrow, at the start of the range.
+    Diagram { lesson: "slice_type::a", live: slice_type_a },
+];
+
+fn bye() -> Vec<Snapshot> {
+    let s1 = String::from("hello");
+    let first = Snapshot::new("let s1 = String::from(\"hello\");").frame("bye").string("s1", &s1);
+
+    let s2 = s1;
+    let second = Snapshot::new("let s2 = s1;").frame("bye").moved("s1", "s2").string("s2", &s2);
+
+    vec![first, second]
+}
+
+fn cloning() -> Vec<Snapshot> {
+    let s1 = String::from("hello");
+    let first = Snapshot::new("let s1 = String::from(\"hello\");").frame("cloning").string("s1", &s1);
+
+    let s2 = s1.clone();
+    let second = Snapshot::new("let s2 = s1.clone();").frame("cloning").string("s1", &s1).string("s2", &s2);
+
+    vec![first, second]
+}
+
+fn slice_type_a() -> Vec<Snapshot> {
+    let s = String::from("hello world");
+    let first = Snapshot::new("let s = String::from(\"hello world\");").frame("a").string("s", &s);
+
+    let hello = &s[0..5];
+    let second = Snapshot::new("let hello = &s[0..5];").frame("a").string("s", &s).str("hello", hello);
+
+    let world = &s[6..11];
+    let third = Snapshot::new("let world = &s[6..11];").frame("a").string("s", &s).str("hello", hello).str("world", world);
+
+    vec![first, second, third]
+}
+
+pub fn find(lesson: &str) -> Option<&'static Diagram> {
+    DIAGRAMS.iter().find(|diagram| diagram.lesson == lesson)
+}
+
+impl Diagram {
+    /// A snapshot after each line of the lesson that changes the stack or the heap.
+    pub fn snapshots(&self) -> Result<Vec<Snapshot>, String> {
+        let source = lesson::function_source(self.lesson).ok_or_else(|| format!("there is no lesson `{}`", self.lesson))?;
+        let trace = simulator::simulate(&source).map_err(|err| format!("can't simulate {}: {}", self.lesson, err))?;
+        match trace.error {
+            Some(error) => Err(format!("{} stops with an error: {}", self.lesson, error)),
+            None => Ok(trace.steps.into_iter().map(|step| step.snapshot).collect()),
+        }
+    }
+}
@@ -0,0 +1,278 @@
+// Tracing Drops ---
+// The comments say in which order values are dropped at a closing brace, and that a variable that was moved
+// is not dropped at all. `Traced<T>` shows it happening: it wraps a value, and tells an `EventLog` when it is moved
+// into another variable and when it is dropped.
+//
+// Variables are dropped in the reverse order they were declared, so when a variable is dropped,
+// the moved-from variables declared after it in the same scope have just gone out of scope too, with nothing to do.
+// A `Scope` marks the start of a function body so the log knows which variables belong together.
+//
+// Each event that makes a binding tells where in the source it happened, so the log can be read next to the code.
+
+use std::any;
+use std::cell::RefCell;
+use std::fmt;
+use std::mem;
+use std::ops::Deref;
+use std::panic::Location;
+use std::rc::Rc;
+
+/// What happened to every traced value, in order. Clones share the same log.
+#[derive(Clone, Default)]
+pub struct EventLog {
+    inner: Rc<RefCell<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    events: Vec<String>,
+    /// Print every event as it happens, to see it between what the example itself prints.
+    echo: bool,
+    bindings: Vec<Binding>,
+    /// The open scopes, innermost last, each with its bindings in declaration order.
+    scopes: Vec<(String, Vec<usize>)>,
+    /// Bindings made by `moved_to`, that join the next scope if one starts right away: they are its parameters.
+    pending: Vec<usize>,
+}
+
+struct Binding {
+    name: String,
+    state: State,
+}
+
+#[derive(PartialEq)]
+enum State {
+    Live,
+    Moved { into: String },
+    /// Still live after the end of its function's body: a parameter about to be dropped, or the return value.
+    Left { function: String },
+    /// The end of the binding was already told.
+    Done,
+}
+
+impl EventLog {
+    pub fn new() -> EventLog {
+        EventLog::default()
+    }
+
+    /// A log that also prints each event as it happens.
+    pub fn echo() -> EventLog {
+        let log = EventLog::new();
+        log.inner.borrow_mut().echo = true;
+        log
+    }
+
+    pub fn events(&self) -> Vec<String> {
+        self.inner.borrow().events.clone()
+    }
+
+    /// Adds an event of your own, to mark where the example is.
+    pub fn note(&self, text: &str) {
+        self.inner.borrow_mut().record(text.to_string());
+    }
+
+    /// Starts tracing `value`, owned by the variable `name`.
+    #[track_caller]
+    pub fn traced<T>(&self, name: &str, value: T) -> Traced<T> {
+        let at = Location::caller();
+        let id = {
+            let mut inner = self.inner.borrow_mut();
+            let id = inner.declare(name);
+            inner.record(format!("{} comes into scope {}", name, place(at)));
+            id
+        };
+        Traced { value: Some(value), id, log: self.clone() }
+    }
+
+    /// Starts the scope of a function body, until the returned guard is dropped.
+    ///
+    /// Declare the guard first, so that it is dropped after every variable of the function.
+    pub fn scope(&self, function: &str) -> Scope {
+        let mut inner = self.inner.borrow_mut();
+        let params = mem::take(&mut inner.pending);
+        inner.scopes.push((function.to_string(), params));
+        inner.record(format!("call {}", function));
+        Scope { log: self.clone() }
+    }
+}
+
+impl Inner {
+    fn record(&mut self, text: String) {
+        self.flush_pending();
+        if self.echo {
+            println!("    [trace] {}", text);
+        }
+        self.events.push(text);
+    }
+
+    // The bindings `moved_to` made that no scope took as parameters belong to the current scope.
+    fn flush_pending(&mut self) {
+        let pending = mem::take(&mut self.pending);
+        if let Some((_, bindings)) = self.scopes.last_mut() {
+            bindings.extend(pending);
+        }
+    }
+
+    fn declare(&mut self, name: &str) -> usize {
+        self.flush_pending();
+        self.bindings.push(Binding { name: name.to_string(), state: State::Live });
+        let id = self.bindings.len() - 1;
+        if let Some((_, bindings)) = self.scopes.last_mut() {
+            bindings.push(id);
+        }
+        id
+    }
+
+    // Tells that the moved-from bindings of the current scope declared after `position` went out of scope, last declared first.
+    fn moved_out_of_scope_after(&mut self, position: Option<usize>) {
+        let bindings = match self.scopes.last() {
+            Some((_, bindings)) => bindings.clone(),
+            None => return,
+        };
+        let start = position.map_or(0, |position| position + 1);
+
+        for &id in bindings.iter().skip(start).rev() {
+            let binding = &mut self.bindings[id];
+            if let State::Moved { into } = &binding.state {
+                let text = format!("{} goes out of scope, but it was moved into {}, so nothing happens", binding.name, into);
+                binding.state = State::Done;
+                self.record(text);
+            }
+        }
+    }
+}
+
+/// Ends the scope of a function body, see `EventLog::scope`.
+pub struct Scope {
+    log: EventLog,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let mut inner = self.log.inner.borrow_mut();
+        inner.moved_out_of_scope_after(None);
+
+        // Parameters are dropped after the body, and the return value isn't dropped at all: their `Traced` tell what happens.
+        if let Some((function, bindings)) = inner.scopes.pop() {
+            for id in bindings {
+                if inner.bindings[id].state == State::Live {
+                    inner.bindings[id].state = State::Left { function: function.clone() };
+                }
+            }
+        }
+    }
+}
+
+/// A value that tells its `EventLog` when it is moved and when it is dropped.
+pub struct Traced<T> {
+    /// `None` once the value was moved to another `Traced`.
+    value: Option<T>,
+    id: usize,
+    log: EventLog,
+}
+
+impl<T> Traced<T> {
+    /// Moves the value into the variable or parameter `name`, the way `let name = self;` or `f(self)` would.
+    #[track_caller]
+    pub fn moved_to(mut self, name: &str) -> Traced<T> {
+        let at = Location::caller();
+        let value = self.value.take();
+        let mut inner = self.log.inner.borrow_mut();
+
+        let from = inner.bindings[self.id].name.clone();
+        let text = match &inner.bindings[self.id].state {
+            State::Left { function } => format!("{} returns {}, which moves into {} {}", function, from, name, place(at)),
+            _ => format!("{} moved into {} {}", from, name, place(at)),
+        };
+        inner.bindings[self.id].state = match inner.bindings[self.id].state {
+            State::Live => State::Moved { into: name.to_string() },
+            _ => State::Done,
+        };
+        inner.record(text);
+
+        inner.bindings.push(Binding { name: name.to_string(), state: State::Live });
+        let id = inner.bindings.len() - 1;
+        inner.pending.push(id);
+        drop(inner);
+
+        Traced { value, id, log: self.log.clone() }
+    }
+
+    /// Copies the value into the variable or parameter `name`, the original stays valid.
+    #[track_caller]
+    pub fn copied_to(&self, name: &str) -> Traced<T>
+    where
+        T: Copy,
+    {
+        let at = Location::caller();
+        let mut inner = self.log.inner.borrow_mut();
+        let text = format!("{} is copied into {} {}, and is still valid", inner.bindings[self.id].name, name, place(at));
+        inner.record(text);
+
+        inner.bindings.push(Binding { name: name.to_string(), state: State::Live });
+        let id = inner.bindings.len() - 1;
+        inner.pending.push(id);
+        drop(inner);
+
+        Traced { value: self.value, id, log: self.log.clone() }
+    }
+
+    pub fn name(&self) -> String {
+        self.log.inner.borrow().bindings[self.id].name.clone()
+    }
+
+    pub fn log(&self) -> &EventLog {
+        &self.log
+    }
+}
+
+impl<T> Deref for Traced<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value.as_ref().expect("a Traced value is only emptied when it is moved")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Traced<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T> Drop for Traced<T> {
+    fn drop(&mut self) {
+        // A moved-from `Traced` is dropped by `moved_to` right away, its variable is told about when its scope ends.
+        if self.value.is_none() {
+            return;
+        }
+
+        let mut inner = self.log.inner.borrow_mut();
+        inner.flush_pending();
+        // A parameter is dropped after the scope of its function ended, and there is nothing more to tell about it.
+        let position = inner.scopes.last().and_then(|(_, bindings)| bindings.iter().position(|&id| id == self.id));
+        if position.is_some() {
+            inner.moved_out_of_scope_after(position);
+        }
+
+        let binding = &mut inner.bindings[self.id];
+        binding.state = State::Done;
+        let text = if mem::needs_drop::<T>() {
+            format!("{} goes out of scope and is dropped: drop frees its memory", binding.name)
+        } else {
+            format!("{} goes out of scope, its {} has nothing to free", binding.name, short_type_name::<T>())
+        };
+        inner.record(text);
+    }
+}
+
+// Where a binding is made, as `at src/ownership.rs:291`.
+fn place(at: &Location) -> String {
+    format!("at {}:{}", at.file(), at.line())
+}
+
+// `alloc::string::String` is better known as `String`.
+fn short_type_name<T>() -> &'static str {
+    let name = any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
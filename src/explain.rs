@@ -0,0 +1,239 @@
+// Explaining Errors ---
+// `rustc --explain E0502` explains an error with an example of its own. This explains the errors the examples fail with
+// using the chapters instead: every example that fails with the error, with the paragraphs that come with it,
+// and then the lessons that show how to write the same thing so that it compiles.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::book::{self, Block};
+use crate::compile_fail::{self, Example};
+use crate::lesson::{self, CHAPTERS};
+use crate::quiz;
+use crate::rustc::{self, Diagnostic};
+
+/// The lesson that fixes each example that doesn't compile.
+pub const FIXES: &[(&str, &str)] = &[
+    ("ownership::error", "ownership::cloning"),
+    // The line of change that main_four calls is commented out, it changes a String through a `&` reference.
+    ("references_and_borrowing::main_four", "references_and_borrowing::main_five"),
+    ("references_and_borrowing::fail", "references_and_borrowing::asd"),
+    ("references_and_borrowing::dsa", "references_and_borrowing::ddas"),
+    ("references_and_borrowing::dangle", "references_and_borrowing::no_dangle"),
+    // Both clear a String while it is still borrowed, main_fixed is done with its slice before it clears.
+    ("slice_type::main", "slice_type::main_fixed"),
+    ("slice_type::main_same", "slice_type::main_fixed"),
+];
+
+/// An error code, explained with the examples.
+#[derive(Debug)]
+pub struct Explanation {
+    pub code: String,
+    /// What the error means, in a line, as the quiz puts it.
+    pub meaning: &'static str,
+    /// The examples that fail with the error.
+    pub fails: Vec<Passage>,
+    /// The lessons that fix them.
+    pub fixed: Vec<Passage>,
+}
+
+/// A block of code of a chapter, with the paragraphs around it.
+#[derive(Debug)]
+pub struct Passage {
+    pub lesson: String,
+    pub title: &'static str,
+    /// Whether a line the chapter comments out, because it doesn't compile, is put back in `code`.
+    pub put_back: bool,
+    /// The paragraph that introduces the code.
+    pub before: Option<String>,
+    pub code: String,
+    /// The errors rustc gives for `code`, on lines counted in it. Empty for the code that compiles.
+    pub errors: Vec<Diagnostic>,
+    /// The paragraphs that discuss the code, up to the one that introduces the next block.
+    pub after: Vec<String>,
+}
+
+/// The error codes the chapters show, sorted: the ones the examples fail with,
+/// and the ones quoted next to a line that is commented out because it doesn't compile.
+pub fn codes() -> Vec<&'static str> {
+    let mut codes: Vec<&str> = compile_fail::EXAMPLES.iter().flat_map(Example::expected_codes).collect();
+    let quotes = CHAPTERS.iter().flat_map(|chapter| chapter.source.lines()).filter_map(commented_out);
+    codes.extend(quotes.flat_map(|(_, quote)| compile_fail::error_codes(quote)));
+    codes.sort_unstable();
+    codes.dedup();
+    codes
+}
+
+/// What an error code means, in a line, as the quiz puts it.
+pub fn meaning(code: &str) -> Option<&'static str> {
+    quiz::ERRORS.iter().find(|(known, _)| *known == code).map(|(_, meaning)| *meaning)
+}
+
+/// Explains an error code, written `E0502`, `e0502` or `0502`. `None` if nothing in the chapters fails with it.
+/// The errors are the ones the local rustc gives, run in `dir`.
+pub fn explain(code: &str, dir: &Path) -> io::Result<Option<Explanation>> {
+    let code = code.trim().to_uppercase();
+    let code = if code.starts_with('E') { code } else { format!("E{}", code) };
+    let meaning = match meaning(&code) {
+        Some(meaning) => meaning,
+        None => return Ok(None),
+    };
+
+    let mut fails = Vec::new();
+    for chapter in CHAPTERS {
+        for lesson in chapter.lessons {
+            let id = format!("{}::{}", chapter.module, lesson.name);
+            let mut passage = match passage(&id) {
+                Some(passage) => passage,
+                None => continue,
+            };
+            let codes: Vec<String> = match compile_fail::find(&id) {
+                Some(example) => example.expected_codes().into_iter().map(String::from).collect(),
+                None => passage.put_back(),
+            };
+            if codes.contains(&code) {
+                passage.check(dir)?;
+                fails.push(passage);
+            }
+        }
+    }
+
+    let mut fixes: Vec<&str> = Vec::new();
+    for passage in &fails {
+        if let Some(&(_, fix)) = FIXES.iter().find(|(lesson, _)| *lesson == passage.lesson) {
+            if !fixes.contains(&fix) {
+                fixes.push(fix);
+            }
+        }
+    }
+
+    Ok(Some(Explanation { meaning, fails, fixed: fixes.into_iter().filter_map(passage).collect(), code }))
+}
+
+// A line of code commented out because it doesn't compile, with the error quoted after it, as in
+// `// some_string.push_str(", world"); <--------- error[E0596]: ...`: the code and the quote.
+fn commented_out(line: &str) -> Option<(&str, &str)> {
+    let (code, quote) = line.trim().strip_prefix("//")?.split_once("<-")?;
+    let quote = quote.trim_start_matches('-').trim();
+    if quote.starts_with("error[E") {
+        Some((code.trim(), quote))
+    } else {
+        None
+    }
+}
+
+// A line without the error its comment quotes, as in `s.clear(); // error[E0502]: ...`, or rustc's `// ^^^^` under it.
+// `None` if the quote was the whole line.
+fn without_quote(line: &str) -> Option<&str> {
+    let (code, comment) = lesson::split_comment(line);
+    if !comment.contains("error[E") && !comment.starts_with("// ^") {
+        Some(line)
+    } else if code.trim().is_empty() {
+        None
+    } else {
+        Some(code.trim_end())
+    }
+}
+
+impl Passage {
+    // Puts back the lines that are commented out because they don't compile, and gives the codes quoted next to them.
+    fn put_back(&mut self) -> Vec<String> {
+        let mut codes = Vec::new();
+        let mut lines = Vec::new();
+        for line in self.code.lines() {
+            match commented_out(line) {
+                Some((code, quote)) => {
+                    codes.extend(compile_fail::error_codes(quote).into_iter().map(String::from));
+                    let indent = &line[..line.len() - line.trim_start().len()];
+                    lines.push(format!("{}{}", indent, code));
+                }
+                None => lines.push(line.to_string()),
+            }
+        }
+        if !codes.is_empty() {
+            self.put_back = true;
+            self.code = lines.join("\n");
+        }
+        codes
+    }
+
+    // Runs rustc on the code as it is displayed, so that the errors point at its lines.
+    // The errors the comments quote are taken out first: rustc's own, under the lines they point at, replace them.
+    fn check(&mut self, dir: &Path) -> io::Result<()> {
+        self.code = self.code.lines().filter_map(without_quote).collect::<Vec<_>>().join("\n");
+        let name = format!("explain_{}", self.lesson.replace("::", "_"));
+        self.errors = rustc::check(&self.code, &name, dir)?
+            .into_iter()
+            .filter(|diagnostic| diagnostic.level == "error" && diagnostic.code.is_some())
+            .collect();
+        Ok(())
+    }
+}
+
+// The block of the book the lesson is in, with what the book says before and after it.
+fn passage(id: &str) -> Option<Passage> {
+    let (chapter, lesson) = lesson::find(id)?;
+    let example = compile_fail::find(id).map(|example| book::strip_annotations(example.source).0);
+
+    book::sections(chapter).into_iter().find_map(|section| {
+        let blocks = section.blocks;
+        let (i, code) = blocks.iter().enumerate().find_map(|(i, block)| match (block, &example) {
+            (Block::Fails { code, .. }, Some(example)) if code == example => Some((i, code.clone())),
+            (Block::Code(code), None) if code.lines().any(|line| lesson::is_signature_of(line, lesson.name)) => Some((i, code.clone())),
+            _ => None,
+        })?;
+
+        let prose = |block: &Block| match block {
+            Block::Prose(text) => Some(text.clone()),
+            _ => None,
+        };
+        let before = i.checked_sub(1).and_then(|previous| prose(&blocks[previous]));
+        let mut after: Vec<String> = blocks[i + 1..].iter().map_while(prose).collect();
+        // The last paragraph before more code introduces that code, not this one.
+        if i + 1 + after.len() < blocks.len() {
+            after.pop();
+        }
+
+        Some(Passage { lesson: id.to_string(), title: lesson.title, put_back: false, before, code, errors: Vec::new(), after })
+    })
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.meaning)?;
+        for passage in &self.fails {
+            let put_back = if passage.put_back { ", with the line that is commented out put back" } else { "" };
+            write!(f, "\n\n== this doesn't compile{}: {} ({}){}", put_back, passage.lesson, passage.title, passage)?;
+        }
+        for passage in &self.fixed {
+            write!(f, "\n\n== this does: {} ({}){}", passage.lesson, passage.title, passage)?;
+        }
+        Ok(())
+    }
+}
+
+// The code is indented, with each error under the line it points at and a caret under the column, the way rustc marks it.
+impl fmt::Display for Passage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(before) = &self.before {
+            write!(f, "\n\n{}", before)?;
+        }
+        writeln!(f)?;
+        for (i, line) in self.code.lines().enumerate() {
+            if line.is_empty() {
+                writeln!(f)?;
+            } else {
+                write!(f, "\n    {}", line)?;
+            }
+            for error in self.errors.iter().filter(|error| error.line == Some(i + 1)) {
+                let column = error.column.map_or(0, |column| column - 1);
+                write!(f, "\n    {}^ error[{}]: {}", " ".repeat(column), error.code.as_deref().unwrap_or_default(), error.message)?;
+            }
+        }
+        for paragraph in &self.after {
+            write!(f, "\n\n{}", paragraph)?;
+        }
+        Ok(())
+    }
+}
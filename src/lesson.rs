@@ -0,0 +1,267 @@
+// Lesson Registry ---
+// Every chapter module keeps its examples as free functions next to the prose that explains them.
+// The registry gives each of those functions a name and a description so the binary can find and run them.
+
+use std::borrow::Cow;
+use std::env;
+use std::io::{self, Read};
+use std::process::Command;
+
+use crate::alloc_tracker::{self, Timeline};
+use crate::{book, compile_fail, ownership, references_and_borrowing, slice_type, the_stack_and_the_heap};
+
+/// Set on a child process to make the binary run a single lesson and exit, see `run_captured`.
+pub const RUN_LESSON_ENV: &str = "RUST_OWNERSHIP_RUN_LESSON";
+
+/// Set along with `RUN_LESSON_ENV` to have the lesson's allocations written out with what it prints, see `run_tracked`.
+pub const TRACK_ENV: &str = "RUST_OWNERSHIP_TRACK";
+
+/// A module of the crate, with the examples it teaches.
+pub struct Chapter {
+    /// Module name, used as the first half of a lesson id (`slice_type::first_word`).
+    pub module: &'static str,
+    /// The whole source file of the module, comments included.
+    pub source: &'static str,
+    pub title: &'static str,
+    pub lessons: &'static [Lesson],
+}
+
+/// One example function of a chapter.
+pub struct Lesson {
+    /// The function name in the chapter module.
+    pub name: &'static str,
+    /// The `// Section ---` heading the example lives under.
+    pub section: &'static str,
+    pub title: &'static str,
+    /// `None` for the examples that are there to show a compile error.
+    pub run: Option<fn()>,
+}
+
+/// All chapters, in reading order.
+pub const CHAPTERS: [&Chapter; 4] = [
+    &ownership::CHAPTER,
+    &the_stack_and_the_heap::CHAPTER,
+    &references_and_borrowing::CHAPTER,
+    &slice_type::CHAPTER,
+];
+
+/// Every lesson paired with its chapter, in reading order.
+pub fn all() -> impl Iterator<Item = (&'static Chapter, &'static Lesson)> {
+    CHAPTERS
+        .iter()
+        .flat_map(|chapter| chapter.lessons.iter().map(move |lesson| (*chapter, lesson)))
+}
+
+/// Looks up a lesson by its `module::function` id.
+pub fn find(id: &str) -> Option<(&'static Chapter, &'static Lesson)> {
+    let (chapter, name) = chapter_of(id)?;
+    let lesson = chapter.lessons.iter().find(|lesson| lesson.name == name)?;
+
+    Some((chapter, lesson))
+}
+
+impl Chapter {
+    /// The `module::function` id of one of this chapter's lessons.
+    pub fn id(&self, lesson: &Lesson) -> String {
+        format!("{}::{}", self.module, lesson.name)
+    }
+
+    /// The source of a lesson with the comments written right above and right below it.
+    ///
+    /// The examples that don't compile live in their own file, which is returned whole, without its `//~` annotations.
+    /// Returns `None` if the function can't be found in the module source.
+    pub fn source_of(&self, lesson: &Lesson) -> Option<Cow<'static, str>> {
+        function_source(&self.id(lesson))
+    }
+
+    /// The lines the function `name` of the module is defined on, see `function_lines`.
+    pub fn function_lines(&self, name: &str) -> Option<(usize, usize)> {
+        let lines: Vec<&str> = self.source.lines().collect();
+        let signature = lines.iter().position(|line| is_signature_of(line, name))?;
+        Some((signature + 1, end_of_function(&lines, signature) + 1))
+    }
+}
+
+/// The source of any function of the chapter modules, `module::function`, see `Chapter::source_of`.
+pub fn function_source(id: &str) -> Option<Cow<'static, str>> {
+    if let Some(example) = compile_fail::find(id) {
+        return Some(Cow::Owned(book::strip_annotations(example.source.trim_end()).0));
+    }
+
+    let (chapter, name) = chapter_of(id)?;
+    let source = chapter.source;
+    let lines: Vec<&str> = source.lines().collect();
+    let (signature, end) = chapter.function_lines(name)?;
+
+    // The comments directly above the function introduce it.
+    let mut first = signature - 1;
+    while first > 0 && lines[first - 1].trim_start().starts_with("//") {
+        first -= 1;
+    }
+
+    // And the comments directly below explain what happened.
+    let mut last = end - 1;
+    while last + 1 < lines.len() && lines[last + 1].trim_start().starts_with("//") {
+        last += 1;
+    }
+
+    let start = offset_of(source, lines[first]);
+    let end = offset_of(source, lines[last]) + lines[last].len();
+    Some(Cow::Borrowed(&source[start..end]))
+}
+
+/// The lines a function of the chapter modules is defined on, from its signature to its closing brace, counting from 1.
+/// An example that doesn't compile is its whole file, see `compile_fail::Example::path`.
+pub fn function_lines(id: &str) -> Option<(usize, usize)> {
+    if let Some(example) = compile_fail::find(id) {
+        return Some((1, example.source.lines().count()));
+    }
+
+    let (chapter, name) = chapter_of(id)?;
+    chapter.function_lines(name)
+}
+
+// The chapter of a `module::function` id, and the name of the function.
+fn chapter_of(id: &str) -> Option<(&'static Chapter, &str)> {
+    let (module, name) = id.split_once("::")?;
+    let chapter = CHAPTERS.iter().find(|chapter| chapter.module == module)?;
+    Some((chapter, name))
+}
+
+/// The line that closes the function whose signature is on line `signature`, both counted from 0 in `lines`:
+/// the one that closes its first brace.
+pub fn end_of_function(lines: &[&str], signature: usize) -> usize {
+    let mut depth = 0;
+    let mut last = signature;
+    for (i, line) in lines.iter().enumerate().skip(signature) {
+        let (opened, closed) = braces(split_comment(line).0);
+        depth += opened - closed;
+        last = i;
+        if depth == 0 && closed > 0 {
+            break;
+        }
+    }
+    last
+}
+
+/// How many braces `code` leaves open, not counting the ones in comments and in string or char literals.
+pub fn depth(code: &str) -> isize {
+    code.lines().map(|line| braces(split_comment(line).0)).map(|(opened, closed)| opened - closed).sum()
+}
+
+// How many blocks a line of code opens and closes: the braces of `"{}"` or `'{'` are not blocks.
+fn braces(code: &str) -> (isize, isize) {
+    let code = code.replace("'{'", "").replace("'}'", "");
+    let (mut opened, mut closed) = (0, 0);
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in code.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => opened += 1,
+            '}' if !in_string => closed += 1,
+            _ => {}
+        }
+    }
+    (opened, closed)
+}
+
+/// Whether `line` starts the definition of the function `name`, as `fn name(` or `pub fn name (`.
+pub fn is_signature_of(line: &str, name: &str) -> bool {
+    let line = line.strip_prefix("pub ").unwrap_or(line);
+    match line.strip_prefix("fn ").and_then(|rest| rest.strip_prefix(name)) {
+        Some(rest) => rest.trim_start().starts_with('('),
+        None => false,
+    }
+}
+
+// `line` must be a slice of `source`.
+fn offset_of(source: &str, line: &str) -> usize {
+    line.as_ptr() as usize - source.as_ptr() as usize
+}
+
+/// A line split where its `//` comment starts, if it has one outside of a string literal.
+pub fn split_comment(line: &str) -> (&str, &str) {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '/' if !in_string && line[i..].starts_with("//") => return line.split_at(i),
+            _ => {}
+        }
+    }
+    (line, "")
+}
+
+/// `source` without its comments, and without the lines that only had a comment.
+pub fn strip_comments(source: &str) -> String {
+    source
+        .lines()
+        .filter_map(|line| {
+            let (code, comment) = split_comment(line);
+            let code = code.trim_end();
+            if code.trim().is_empty() && !comment.is_empty() {
+                None
+            } else {
+                Some(code)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Runs a lesson in a child process and returns what it printed.
+///
+/// The child is this same binary with `RUN_LESSON_ENV` set, so the binary must call `run_requested`
+/// before doing anything else.
+pub fn run_captured(id: &str) -> io::Result<String> {
+    let output = Command::new(env::current_exe()?).env(RUN_LESSON_ENV, id).output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(format!("`{}` failed: {}", id, stderr.trim())));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Runs a lesson in a child process like `run_captured` and records what it allocates, in order with what it prints,
+/// see `alloc_tracker::stream`. The binary must also install `TrackingAllocator`.
+pub fn run_tracked(id: &str) -> io::Result<Timeline> {
+    let (mut reader, writer) = io::pipe()?;
+    let mut child = Command::new(env::current_exe()?)
+        .env(RUN_LESSON_ENV, id)
+        .env(TRACK_ENV, "1")
+        .stdout(writer.try_clone()?)
+        .stderr(writer)
+        .spawn()?;
+
+    // The command, and with it the writing end of the pipe, is gone by now, so this reads until the child exits.
+    let mut output = String::new();
+    reader.read_to_string(&mut output)?;
+    if !child.wait()?.success() {
+        return Err(io::Error::other(format!("`{}` failed: {}", id, output.trim())));
+    }
+
+    Ok(Timeline::read(&output))
+}
+
+/// Runs the lesson named by `RUN_LESSON_ENV`, if any, and reports whether it did.
+pub fn run_requested() -> bool {
+    let id = match env::var(RUN_LESSON_ENV) {
+        Ok(id) => id,
+        Err(_) => return false,
+    };
+
+    match find(&id) {
+        Some((_, Lesson { run: Some(run), .. })) if env::var_os(TRACK_ENV).is_some() => alloc_tracker::stream(run),
+        Some((_, Lesson { run: Some(run), .. })) => run(),
+        _ => panic!("`{}` is not a runnable lesson", id),
+    }
+    true
+}
@@ -0,0 +1,32 @@
+// The examples are written to be read, not used: most of them bind values only to show what happens to them,
+// and some are deliberately less idiomatic than they could be (`&String` parameters) because the text improves on them later.
+// The examples that don't compile are `mod`s of the chapters that are only there with the `show-errors` feature:
+// build with `--features show-errors` to see their errors. Their source is under tests/compile-fail, see `compile_fail`.
+#[allow(unused_variables, clippy::ptr_arg, clippy::let_and_return)]
+pub mod ownership;
+#[allow(unused_variables, clippy::ptr_arg, clippy::let_and_return)]
+pub mod references_and_borrowing;
+#[allow(unused_variables, clippy::ptr_arg, clippy::redundant_slicing)]
+pub mod slice_type;
+pub mod the_stack_and_the_heap;
+
+pub mod alloc_tracker;
+pub mod book;
+pub mod checked_slice;
+pub mod compile_fail;
+pub mod diagram;
+pub mod drop_trace;
+pub mod explain;
+pub mod json;
+pub mod lesson;
+pub mod manifest;
+pub mod playground;
+pub mod profile;
+pub mod progress;
+pub mod quiz;
+pub mod repl;
+pub mod rustc;
+pub mod simulator;
+pub mod site;
+pub mod slices;
+pub mod words;
@@ -1,286 +1,570 @@
-mod slice_type;
-mod references_and_borrowing;
-mod the_stack_and_the_heap;
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process;
+
+use rust_ownership::alloc_tracker::{self, TrackingAllocator};
+use rust_ownership::book;
+use rust_ownership::compile_fail;
+use rust_ownership::diagram;
+use rust_ownership::explain;
+use rust_ownership::lesson::{self, Chapter, Lesson};
+use rust_ownership::manifest;
+use rust_ownership::playground;
+use rust_ownership::profile::Profile;
+use rust_ownership::progress::{self, Progress, Step};
+use rust_ownership::quiz::{self, Results, Task, Topic};
+use rust_ownership::repl::Session;
+use rust_ownership::rustc;
+use rust_ownership::simulator::{self, Liveness};
+use rust_ownership::site;
+
+// Only records while `alloc` runs a lesson, see `alloc_tracker`.
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+const USAGE: &str = "\
+usage: rust-ownership <command>
+
+commands:
+    list          list every lesson
+    run [<id>]    run a lesson, or all of them, and print what it prints;
+                  for an example that doesn't compile, print what the local rustc says instead
+    show <id>     print the commented source of a lesson
+    alloc <id>    run a lesson and list every heap allocation and deallocation it makes
+    diagram [--live] <id>
+                  draw the stack and the heap after each line of a lesson, as the simulator runs it;
+                  with --live, as the lesson really runs, with the real addresses and capacities
+    export [<id>] <dir>
+                  write the diagrams of a lesson, or of all of them, as SVG and Graphviz DOT files
+    book <dir>    write the chapters as Markdown pages of an mdBook, with a table of contents
+    site <dir>    write the chapters as HTML pages that work offline, with highlighted code and the diagrams
+    manifest [<file>]
+                  describe every example as JSON: where it is, whether it compiles, and what it prints
+    simulate [--lexical] <scenario | file | ->
+                  trace what happens to each value of a program, without compiling it;
+                  with --lexical, borrows last until the end of their scope, as before Rust 2018
+    quiz [<topic>]
+                  answer \"will this compile?\" questions about the examples of a topic, or of all of them
+    exercise [<id>]
+                  list the fill-in-the-blank tasks, or do one: the local rustc builds and runs the answer
+    results       how many questions and tasks of each topic you got right
+    progress      which lessons you have viewed, run and passed in the quiz
+    next          the next lesson to read, after the last one you viewed or ran
+    serve [<port>]
+                  start a playground on http://127.0.0.1:7878 to edit any lesson and run it with the local rustc
+    repl          type statements and see what happens to each value right away
+    explain [<code>]
+                  explain an error code, like E0502, with the examples that fail with it and the ones that fix them
+    check         compile the examples that don't compile with the local rustc and report
+                  the ones whose comments no longer match what it says
+
+lessons are named after their module and function, e.g. `slice_type::first_word`
+progress and results are kept in ~/.rust-ownership/<user>, set RUST_OWNERSHIP_HOME to keep them elsewhere
+and RUST_OWNERSHIP_USER to answer as someone else than the logged in user";
 
 fn main() {
-    // Ownership ----------------------------------------------------------------------------------
-
-    // Ownership is Rust’s most unique feature, and it enables Rust to make memory safety guarantees
-    // without needing a garbage collector. Therefore, it’s important to understand how ownership works in Rust.
-
-    // We’ll talk about ownership as well as several related features: borrowing, slices, and how Rust lays data out in memory.
-
-    // All programs have to manage the way they use a computer’s memory while running.
-    // Some languages have garbage collection that constantly looks for no longer used memory as the program runs;
-    // in other languages, the programmer must explicitly allocate and free the memory.
-
-    // Rust uses a third approach: memory is managed through a system of ownership with a set of rules that the compiler checks at compile time.
-
-    // Ownership Rules ---
-
-    // - Each value in Rust has a variable that’s called its owner.
-    // - There can only be one owner at a time.
-    // - When the owner goes out of scope, the value will be dropped.
+    if lesson::run_requested() {
+        return;
+    }
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let result = match args.as_slice() {
+        ["list"] => list(),
+        ["run"] => run_all(),
+        ["run", id] => find(id).and_then(|(chapter, lesson)| run(chapter, lesson)),
+        ["show", id] => find(id).and_then(|(chapter, lesson)| show(chapter, lesson)),
+        ["alloc", id] => find(id).and_then(|(chapter, lesson)| alloc(chapter, lesson)),
+        ["diagram", id] => diagram(id, false),
+        ["diagram", "--live", id] => diagram(id, true),
+        ["export", dir] => export(diagram::DIAGRAMS.iter().collect(), dir),
+        ["export", id, dir] => diagram::find(id)
+            .ok_or_else(|| format!("no diagram for `{}`", id))
+            .and_then(|diagram| export(vec![diagram], dir)),
+        ["book", dir] => write_book(dir),
+        ["site", dir] => write_site(dir),
+        ["manifest"] => write_manifest(None),
+        ["manifest", file] => write_manifest(Some(file)),
+        ["simulate", what] => simulate(what, Liveness::NonLexical),
+        ["simulate", "--lexical", what] => simulate(what, Liveness::Lexical),
+        ["quiz"] => quiz(quiz::TOPICS.iter().collect()),
+        ["quiz", name] => quiz::topic(name)
+            .ok_or_else(|| format!("no topic named `{}`, the topics are {}", name, topic_names()))
+            .and_then(|topic| quiz(vec![topic])),
+        ["exercise"] => exercises(),
+        ["exercise", id] => quiz::task(id)
+            .ok_or_else(|| format!("no task named `{}`, see `rust-ownership exercise`", id))
+            .and_then(exercise),
+        ["results"] => results(),
+        ["progress"] => show_progress(),
+        ["next"] => next(),
+        ["serve"] => serve(playground::DEFAULT_PORT),
+        ["serve", port] => port.parse().map_err(|_| format!("`{}` is not a port", port)).and_then(serve),
+        ["repl"] => repl(),
+        ["explain"] => explain_codes(),
+        ["explain", code] => explain(code),
+        ["check"] => check(),
+        _ => Err(USAGE.to_string()),
+    };
+
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
 }
 
-// Variable Scope ---
-
-// The variable s refers to a string literal, where the value of the string is hardcoded into the text of our program.
-// The variable is valid from the point at which it’s declared until the end of the current scope.
-
-fn do_something() {
-    // s is not valid here, it’s not yet declared
-    let s = "hello"; // s is valid from this point forward
-    // do stuff with s
-} // this scope is now over, and s is no longer valid
-
-// In other words, there are two important points in time here:
-// - When s comes into scope, it is valid.
-// - It remains valid until it goes out of scope.
-
-// The String Type ---
-// To illustrate the rules of ownership, we need a more complex data type.
-
-// We’ll use String as the example here and concentrate on the parts of String that relate to ownership.
-// These aspects also apply to other complex data types, whether they are provided by the standard library or created by you.
-
-// We’ve already seen string literals, where a string value is hardcoded into our program.
-// String literals are convenient, but they aren’t suitable for every situation in which we may want to use text.
-// One reason is that they’re immutable. Another is that not every string value can be known when we write our code: for example,
-// what if we want to take user input and store it?
-
-// For these situations, Rust has a second string type, String. This type is allocated on the heap and as such is
-// able to store an amount of text that is unknown to us at compile time.
-
-// You can create a String from a string literal using the from function, like so:
-fn do_another_thing() {
-    let s = String::from("hello");
+fn find(id: &str) -> Result<(&'static Chapter, &'static Lesson), String> {
+    lesson::find(id).ok_or_else(|| format!("no lesson named `{}`, see `rust-ownership list`", id))
 }
-// The double colon (::) is an operator that allows us to namespace this particular from function under the
-// String type rather than using some sort of name like string_from.
 
-// This kind of string can be mutated:
-fn do_another_thing_again() {
-    let mut s = String::from("hello");
+fn list() -> Result<(), String> {
+    for chapter in lesson::CHAPTERS.iter() {
+        println!("{}", chapter.title);
 
-    s.push_str(", world!"); // push_str() appends a literal to a String
+        for lesson in chapter.lessons {
+            let note = if lesson.run.is_none() { " (does not compile)" } else { "" };
+            println!("    {:<50} {}{}", chapter.id(lesson), lesson.title, note);
+        }
 
-    println!("{}", s); // This will print `hello, world!`
+        println!();
+    }
+    Ok(())
 }
-// So, what’s the difference here? Why can String be mutated but literals cannot?
-// The difference is how these two types deal with memory.
-
-// Memory and Allocation ---
-
-// In the case of a string literal, we know the contents at compile time, so the text is hardcoded directly into the final executable.
-// This is why string literals are fast and efficient. But these properties only come from the string literal’s immutability.
-// Unfortunately, we can’t put a blob of memory into the binary for each piece of text whose size is unknown at
-// compile time and whose size might change while running the program.
 
-// With the String type, in order to support a mutable, growable piece of text, we need to allocate an amount of memory on the
-// heap, unknown at compile time, to hold the contents. This means:
-// - The memory must be requested from the operating system at runtime.
-// - We need a way of returning this memory to the operating system when we’re done with our String.
-
-// That first part is done by us: when we call String::from, its implementation requests the memory it needs.
-// This is pretty much universal in programming languages.
-
-// However, the second part is different. In languages with a garbage collector (GC), the GC keeps track and cleans up memory that isn’t
-// being used anymore, and we don’t need to think about it. Without a GC, it’s our responsibility to identify when memory is no longer
-// being used and call code to explicitly return it, just as we did to request it.
-
-// Doing this correctly has historically been a difficult programming problem. If we forget, we’ll waste memory. If we do it too early,
-// we’ll have an invalid variable. If we do it twice, that’s a bug too. We need to pair exactly one allocate with exactly one free.
-
-// Rust takes a different path: the memory is automatically returned once the variable that owns it goes out of scope.
-
-fn do_anything() {
-    let s = String::from("hello"); // s is valid from this point forward
-    // do stuff with s
-} // this scope is now over, and s is no longer valid
-
-// There is a natural point at which we can return the memory our String needs to the operating system: when s goes out of scope.
-// When a variable goes out of scope, Rust calls a special function for us.
-// This function is called drop, and it’s where the author of String can put the code to return the memory.
-// Rust calls drop automatically at the closing curly bracket.
-
-// This pattern has a profound impact on the way Rust code is written.
-// It may seem simple right now, but the behavior of code can be unexpected in more complicated situations when we want to have multiple
-// variables use the data we’ve allocated on the heap.
-
-// Ways Variables and Data Interact: Move ---
-// Multiple variables can interact with the same data in different ways in Rust. Let’s look at an example using an integer in Listing 4-2.
-fn hello() {
-    let x = 5;
-    let y = x;
+fn run_all() -> Result<(), String> {
+    for (chapter, lesson) in lesson::all() {
+        if let Err(message) = run_lesson(chapter, lesson) {
+            println!("{}", message);
+        }
+        println!();
+    }
+    Ok(())
 }
 
-// We can probably guess what this is doing: “bind the value 5 to x; then make a copy of the value in x and bind it to y.”
-// We now have two variables, x and y, and both equal 5. This is indeed what is happening, because integers are simple values with a known,
-// fixed size, and these two 5 values are pushed onto the stack.
-
-// Now let’s look at the String version:
-fn bye() {
-    let s1 = String::from("hello");
-    let s2 = s1;
+// Only a lesson the trainee ran on its own, and that did run, counts as run in the progress.
+fn run(chapter: &Chapter, lesson: &Lesson) -> Result<(), String> {
+    if run_lesson(chapter, lesson)? {
+        record_progress(&chapter.id(lesson), Step::Run);
+    }
+    Ok(())
 }
 
-// This looks very similar to the previous code, so we might assume that the way it works would be the same: that is,
-// the second line would make a copy of the value in s1 and bind it to s2. But this isn’t quite what happens.
-
-// A String is made up of three parts: a pointer to the memory that holds the contents of the string, a length, and a capacity.
-// This group of data is stored on the stack. But in the other side we have the memory on the heap that holds the contents.
-
-// The length is how much memory, in bytes, the contents of the String is currently using.
-// The capacity is the total amount of memory, in bytes, that the String has received from the operating system.
-
-// When we assign s1 to s2, the String data is copied, meaning we copy the pointer, the length, and the capacity that are on the stack.
-// We do not copy the data on the heap that the pointer refers to.
-
-// Earlier, we said that when a variable goes out of scope, Rust automatically calls the drop function and cleans up the heap memory for that variable.
-// But in this case both data pointers pointing to the same location. This is a problem: when s2 and s1 go out of scope, they will both try to free the same memory.
-// This is known as a double free error and is one of the memory safety bugs we mentioned previously.
-// Freeing memory twice can lead to memory corruption, which can potentially lead to security vulnerabilities.
-
-// To ensure memory safety, there’s one more detail to what happens in this situation in Rust.
-// Instead of trying to copy the allocated memory, Rust considers s1 to no longer be valid and, therefore,
-// Rust doesn’t need to free anything when s1 goes out of scope.
-
-// Check out what happens when you try to use s1 after s2 is created; it won’t work:
-fn error() {
-    let s1 = String::from("hello");
-    let s2 = s1;
-
-    println!("{}, world!", s1);
+// Runs a lesson and prints what it prints, and reports whether it ran: an example that doesn't compile can't.
+fn run_lesson(chapter: &Chapter, lesson: &Lesson) -> Result<bool, String> {
+    let id = chapter.id(lesson);
+    println!("== {} ({})", id, lesson.title);
+
+    if lesson.run.is_none() {
+        println!("this example does not compile, it is here to show the error, see `rust-ownership show {}`", id);
+        println!("or build the crate with `--features show-errors` to get the error from cargo");
+        if let Some(example) = compile_fail::find(&id) {
+            show_errors(example);
+        }
+        return Ok(false);
+    }
+
+    let stdout = lesson::run_captured(&id).map_err(|err| err.to_string())?;
+    if stdout.is_empty() {
+        println!("(nothing printed)");
+    } else {
+        print!("{}", stdout);
+    }
+    Ok(true)
 }
-// we get: error[E0382]: borrow of moved value: `s1`
-
-// If you’ve heard the terms shallow copy and deep copy while working with other languages, the concept of copying the pointer,
-// length, and capacity without copying the data probably sounds like making a shallow copy.
-// But because Rust also invalidates the first variable, instead of being called a shallow copy, it’s known as a move.
-// In this example, we would say that s1 was moved into s2.
-
-// That solves our problem! With only s2 valid, when it goes out of scope, it alone will free the memory, and we’re done.
-//
-// In addition, there’s a design choice that’s implied by this: Rust will never automatically create “deep” copies of your data.
-// Therefore, any automatic copying can be assumed to be inexpensive in terms of runtime performance.
-
-// Ways Variables and Data Interact: Clone ---
-// If we do want to deeply copy the heap data of the String, not just the stack data, we can use a common method called clone.
 
-// Here’s an example of the clone method in action:
-fn cloning() {
-    let s1 = String::from("hello");
-    let s2 = s1.clone();
-
-    println!("s1 = {}, s2 = {}", s1, s2);
+// What rustc says about an example that doesn't compile, the way `cargo build` would print it.
+fn show_errors(example: &compile_fail::Example) {
+    let dir = rustc::temp_dir("run");
+    let checked = rustc::check(&book::strip_annotations(example.source).0, &example.lesson.replace("::", "_"), &dir);
+    let _ = fs::remove_dir_all(&dir);
+    match checked {
+        Ok(diagnostics) => {
+            println!();
+            for diagnostic in diagnostics.iter().filter(|diagnostic| diagnostic.code.is_some()) {
+                print!("{}", diagnostic.rendered);
+            }
+        }
+        Err(err) => println!("(can't run rustc to show the error: {})", err),
+    }
 }
 
-// This works just fine and explicitly produces the behavior where the heap data does get copied.
-// When you see a call to clone, you know that some arbitrary code is being executed and that code may be expensive.
-// It’s a visual indicator that something different is going on.
-
-// Stack-Only Data: Copy ---
-// There’s another wrinkle we haven’t talked about yet. This code using integers works and is valid:
-fn lets_go() {
-    let x = 5;
-    let y = x;
+fn show(chapter: &Chapter, lesson: &Lesson) -> Result<(), String> {
+    let source = chapter
+        .source_of(lesson)
+        .ok_or_else(|| format!("can't find the source of `{}`", chapter.id(lesson)))?;
+    record_progress(&chapter.id(lesson), Step::Viewed);
 
-    println!("x = {}, y = {}", x, y);
+    println!("// {} > {}", chapter.title, lesson.section);
+    println!("{}", source);
+    Ok(())
 }
-// But this code seems to contradict what we just learned: we don’t have a call to clone, but x is still valid and wasn’t moved into y.
-
-// The reason is that types such as integers that have a known size at compile time are stored entirely on the stack, so copies of the actual values are quick to make.
-// That means there’s no reason we would want to prevent x from being valid after we create the variable y.
-
-// In other words, there’s no difference between deep and shallow copying here, so calling clone wouldn’t do
-// anything different from the usual shallow copying and we can leave it out.
 
-// Rust has a special annotation called the Copy trait that we can place on types like integers that are stored on the stack.
-// If a type has the Copy trait, an older variable is still usable after assignment. Rust won’t let us annotate a type with the Copy trait if the type,
-// or any of its parts, has implemented the Drop trait.
-// If the type needs something special to happen when the value goes out of scope and we add the Copy annotation to that type, we’ll get a compile-time error.
+fn alloc(chapter: &Chapter, lesson: &Lesson) -> Result<(), String> {
+    if !alloc_tracker::is_installed() {
+        return Err("the tracking allocator is not installed in this binary".to_string());
+    }
+
+    let id = chapter.id(lesson);
+    if lesson.run.is_none() {
+        return Err(format!("`{}` does not compile, so it can't run, see `rust-ownership show {}`", id, id));
+    }
+    let timeline = lesson::run_tracked(&id).map_err(|err| err.to_string())?;
+
+    println!("== {} ({})", id, lesson.title);
+    println!("heap allocations, and what the lesson prints after |, in the order they happened:");
+    print!("{}", timeline);
+    Ok(())
+}
 
-// So what types are Copy? as a general rule, any group of simple scalar values can be Copy, and nothing that requires allocation or is some form of resource is Copy.
-// Here are some of the types that are Copy:
+fn diagram(id: &str, live: bool) -> Result<(), String> {
+    let diagram = diagram::find(id).ok_or_else(|| {
+        let lessons: Vec<&str> = diagram::DIAGRAMS.iter().map(|diagram| diagram.lesson).collect();
+        format!("no diagram for `{}`, there are diagrams for {}", id, lessons.join(", "))
+    })?;
+
+    let snapshots = if live { (diagram.live)() } else { diagram.snapshots()? };
+    for snapshot in snapshots {
+        println!("{}", snapshot);
+    }
+    Ok(())
+}
 
-// - All the integer types, such as u32.
-// - The Boolean type, bool, with values true and false.
-// - All the floating point types, such as f64.
-// - The character type, char.
-// - Tuples, if they only contain types that are also Copy. For example, (i32, i32) is Copy, but (i32, String) is not.
+// One file per step and format, named after the lesson: `ownership-bye-2.svg`.
+fn export(diagrams: Vec<&diagram::Diagram>, dir: &str) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|err| format!("can't create {}: {}", dir, err))?;
+
+    for diagram in diagrams {
+        for (i, snapshot) in diagram.snapshots()?.iter().enumerate() {
+            let name = format!("{}-{}", diagram.lesson.replace("::", "-"), i + 1);
+            for (extension, contents) in [("svg", snapshot.to_svg(&name)), ("dot", snapshot.to_dot())] {
+                let path = Path::new(dir).join(format!("{}.{}", name, extension));
+                fs::write(&path, contents).map_err(|err| format!("can't write {}: {}", path.display(), err))?;
+                println!("wrote {}", path.display());
+            }
+        }
+    }
+    Ok(())
+}
 
+fn write_book(dir: &str) -> Result<(), String> {
+    for path in book::write(Path::new(dir)).map_err(|err| format!("can't write the book to {}: {}", dir, err))? {
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
 
-// Ownership and Functions ---
-// The semantics for passing a value to a function are similar to those for assigning a value to a variable.
-// Passing a variable to a function will move or copy, just as assignment does.
+fn write_site(dir: &str) -> Result<(), String> {
+    for path in site::write(Path::new(dir)).map_err(|err| format!("can't write the site to {}: {}", dir, err))? {
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
 
-fn main_function() {
-    let s = String::from("hello");  // s comes into scope
+fn write_manifest(file: Option<&str>) -> Result<(), String> {
+    let entries = manifest::entries(lesson::run_captured).map_err(|err| err.to_string())?;
+    let json = format!("{}\n", manifest::to_json(&entries));
+    match file {
+        Some(file) => fs::write(file, json).map_err(|err| format!("can't write {}: {}", file, err)),
+        None => {
+            print!("{}", json);
+            Ok(())
+        }
+    }
+}
 
-    takes_ownership(s);     // s's value moves into the function...
-    // ... and so is no longer valid here
+fn simulate(what: &str, liveness: Liveness) -> Result<(), String> {
+    let source = match (simulator::scenario(what), what) {
+        (Some(scenario), _) => scenario.source(),
+        (None, "-") => {
+            let mut source = String::new();
+            io::stdin().read_to_string(&mut source).map_err(|err| err.to_string())?;
+            source
+        }
+        (None, path) => fs::read_to_string(path).map_err(|err| format!("can't read {}: {}", path, err))?,
+    };
+
+    for (i, line) in source.lines().enumerate() {
+        println!("{:>3} | {}", i + 1, line);
+    }
+    println!();
+
+    let trace = simulator::simulate_with(&source, liveness).map_err(|err| err.to_string())?;
+    print!("{}", trace);
+    Ok(())
+}
 
-    let x = 5;                    // x comes into scope
+fn topic_names() -> String {
+    quiz::TOPICS.iter().map(Topic::slug).collect::<Vec<_>>().join(", ")
+}
 
-    makes_copy(x);          // x would move into the function,
-    // but i32 is Copy, so it’s okay to still use x afterward
-} // Here, x goes out of scope, then s. But because s's value was moved, nothing special happens.
+// Reads a line of the answer, `None` at the end of the input.
+fn ask(prompt: &str) -> Result<Option<String>, String> {
+    print!("{}", prompt);
+    io::stdout().flush().map_err(|err| err.to_string())?;
 
-fn takes_ownership(some_string: String) { // some_string comes into scope
-    println!("{}", some_string);
-} // Here, some_string goes out of scope and `drop` is called. The backing
-// memory is freed.
+    let mut line = String::new();
+    match io::stdin().read_line(&mut line).map_err(|err| err.to_string())? {
+        0 => Ok(None),
+        _ => Ok(Some(line.trim().to_string())),
+    }
+}
 
-fn makes_copy(some_integer: i32) { // some_integer comes into scope
-    println!("{}", some_integer);
-} // Here, some_integer goes out of scope. Nothing special happens.
+fn load_results() -> Result<(Profile, Results), String> {
+    let profile = Profile::current().map_err(|err| err.to_string())?;
+    let value = profile.load(quiz::RESULTS_FILE).map_err(|err| format!("can't read the results of {}: {}", profile.user, err))?;
+    Ok((profile, Results::from_json(&value)))
+}
 
-// If we tried to use s after the call to takes_ownership, Rust would throw a compile-time error. These static checks protect us from mistakes.
-// Try adding code to main that uses s and x to see where you can use them and where the ownership rules prevent you from doing so.
+fn save_results(profile: &Profile, results: &Results) -> Result<(), String> {
+    profile.save(quiz::RESULTS_FILE, &results.to_json()).map_err(|err| format!("can't save the results: {}", err))
+}
 
-// Return Values and Scope ---
-// Returning values can also transfer ownership.
+fn quiz(topics: Vec<&Topic>) -> Result<(), String> {
+    let (profile, mut results) = load_results()?;
+    let (_, mut progress) = load_progress()?;
+    let (mut asked, mut right) = (0, 0);
+
+    for topic in topics {
+        for question in topic.questions() {
+            println!("== {}: will this compile?", topic.name);
+            println!();
+            println!("{}", question.code);
+            println!();
+            for (i, choice) in question.choices.iter().enumerate() {
+                println!("{}) {}", i + 1, choice);
+            }
+
+            let correct = loop {
+                let answer = match ask("your answer (q to stop): ")? {
+                    Some(answer) if answer != "q" => answer,
+                    _ => {
+                        println!();
+                        println!("{} right out of {}", right, asked);
+                        return Ok(());
+                    }
+                };
+                match question.grade(&answer) {
+                    Some(correct) => break correct,
+                    None => println!("answer with a number from 1 to {}", question.choices.len()),
+                }
+            };
+
+            asked += 1;
+            if correct {
+                right += 1;
+                println!("right!");
+            } else {
+                println!("no: {}", question.choices[question.answer]);
+            }
+            println!("see `rust-ownership show {}` for the explanation", question.lesson);
+            println!();
+
+            results.record(&question.id, correct);
+            save_results(&profile, &results)?;
+            if correct {
+                progress.record(&question.lesson, Step::Passed);
+                save_progress(&profile, &progress)?;
+            }
+        }
+    }
+
+    println!("{} right out of {}", right, asked);
+    Ok(())
+}
 
-fn another_main_function() {
-    let s1 = gives_ownership();         // gives_ownership moves its return value into s1
+fn exercises() -> Result<(), String> {
+    let (_, results) = load_results()?;
+    for task in quiz::TASKS {
+        let done = if results.passed(&task.result_id()) { "done" } else { "" };
+        println!("    {:<20} {:<20} {:<5} {}", task.id, task.topic, done, task.prompt);
+    }
+    Ok(())
+}
 
-    let s2 = String::from("hello");     // s2 comes into scope
+fn exercise(task: &Task) -> Result<(), String> {
+    let (profile, mut results) = load_results()?;
+
+    println!("== {}: {}", task.topic, task.prompt);
+    println!();
+    print!("{}", task.template);
+    println!();
+
+    let answer = match ask(&format!("what goes in place of {}? ", quiz::BLANK))? {
+        Some(answer) => answer,
+        None => return Ok(()),
+    };
+
+    let dir = rustc::temp_dir("exercise");
+    let grade = task.grade(&answer, &dir);
+    let _ = fs::remove_dir_all(&dir);
+    let grade = grade.map_err(|err| format!("can't run rustc: {}", err))?;
+    results.record(&task.result_id(), grade.passed);
+    save_results(&profile, &results)?;
+
+    if grade.passed {
+        println!("it compiles and prints what it should, well done!");
+        return Ok(());
+    }
+    if let Some(printed) = grade.printed {
+        print!("it compiles, but it prints:\n{}instead of:\n{}", printed, task.output);
+        return Err("not yet: try again".to_string());
+    }
+    for error in &grade.errors {
+        print!("{}", error.rendered);
+    }
+    Err("not yet: that doesn't compile, try again".to_string())
+}
 
-    let s3 = takes_and_gives_back(s2);  // s2 is moved into takes_and_gives_back, which also moves its return value into s3
-} // Here, s3 goes out of scope and is dropped. s2 goes out of scope but was moved, so nothing happens. s1 goes out of scope and is dropped.
+fn results() -> Result<(), String> {
+    let (profile, results) = load_results()?;
+    println!("results of {}", profile.user);
+
+    for topic in quiz::TOPICS {
+        let questions = topic.questions();
+        let tasks: Vec<&Task> = topic.tasks().collect();
+        let right = questions.iter().filter(|question| results.passed(&question.id)).count();
+        let passed = tasks.iter().filter(|task| results.passed(&task.result_id())).count();
+        println!("    {:<20} questions {:>2}/{:<2}   tasks {}/{}", topic.name, right, questions.len(), passed, tasks.len());
+    }
+    Ok(())
+}
 
-// gives_ownership will move its return value into the function that calls it
-fn gives_ownership() -> String {
-    let some_string = String::from("hello"); // some_string comes into scope
+fn load_progress() -> Result<(Profile, Progress), String> {
+    let profile = Profile::current().map_err(|err| err.to_string())?;
+    let value = profile
+        .load(progress::PROGRESS_FILE)
+        .map_err(|err| format!("can't read the progress of {}: {}", profile.user, err))?;
+    Ok((profile, Progress::from_json(&value)))
+}
 
-    some_string // some_string is returned and moves out to the calling function
+fn save_progress(profile: &Profile, progress: &Progress) -> Result<(), String> {
+    profile.save(progress::PROGRESS_FILE, &progress.to_json()).map_err(|err| format!("can't save the progress: {}", err))
 }
 
-// takes_and_gives_back will take a String and return another String
-fn takes_and_gives_back(a_string: String) -> String { // a_string comes into scope
-    a_string  // a_string is returned and moves out to the calling function
+// Keeping track is a side effect of `show` and `run`, so a profile that can't be written only gets a warning.
+fn record_progress(id: &str, step: Step) {
+    let recorded = load_progress().and_then(|(profile, mut progress)| {
+        progress.record(id, step);
+        save_progress(&profile, &progress)
+    });
+    if let Err(message) = recorded {
+        eprintln!("warning: {}", message);
+    }
 }
 
-// The ownership of a variable follows the same pattern every time: assigning a value to another variable moves it.
-// When a variable that includes data on the heap goes out of scope, the value will be cleaned up by drop unless the data has been moved to be owned by another variable.
+fn show_progress() -> Result<(), String> {
+    let (profile, progress) = load_progress()?;
+    println!("progress of {}, v: viewed, r: run, p: passed in the quiz", profile.user);
+    println!();
+
+    for chapter in lesson::CHAPTERS.iter() {
+        let lessons: Vec<_> = chapter.lessons.iter().map(|lesson| progress.of(&chapter.id(lesson))).collect();
+        let done = lessons.iter().filter(|lesson| lesson.is_done()).count();
+        println!("{} ({}/{} done)", chapter.title, done, lessons.len());
+
+        for (lesson, state) in chapter.lessons.iter().zip(&lessons) {
+            let mark = |on, letter| if on { letter } else { '-' };
+            let marks: String = [mark(state.viewed, 'v'), mark(state.run, 'r'), mark(state.passed, 'p')].iter().collect();
+            let here = if progress.last.as_deref() == Some(state.id.as_str()) { "  <- last" } else { "" };
+            println!("    {} {:<50} {}{}", marks, state.id, lesson.title, here);
+        }
+        println!();
+    }
+    Ok(())
+}
 
-// Taking ownership and then returning ownership with every function is a bit tedious.
-// What if we want to let a function use a value but not take ownership? It’s quite annoying that anything we pass in also needs to be passed back if we want to use it again,
-// in addition to any data resulting from the body of the function that we might want to return as well.
+fn next() -> Result<(), String> {
+    let (_, progress) = load_progress()?;
+    let (chapter, lesson) = match progress.next() {
+        Some(next) => next,
+        None => {
+            println!("you have viewed and run every lesson, try `rust-ownership quiz`");
+            return Ok(());
+        }
+    };
+
+    let id = chapter.id(lesson);
+    let state = progress.of(&id);
+    println!("next: {} ({}), in {} > {}", id, lesson.title, chapter.title, lesson.section);
+    if !state.viewed {
+        println!("    read it with `rust-ownership show {}`", id);
+    }
+    if !state.run {
+        println!("    run it with `rust-ownership run {}`", id);
+    }
+    Ok(())
+}
 
-// It’s possible to return multiple values using a tuple:
-fn main_two() {
-    let s1 = String::from("hello");
+fn repl() -> Result<(), String> {
+    let mut session = Session::new();
+    println!("type a statement, or :help for the commands");
+
+    let stdin = io::stdin();
+    let mut line = String::new();
+    loop {
+        print!("{}", session.prompt());
+        io::stdout().flush().map_err(|err| err.to_string())?;
+
+        line.clear();
+        if stdin.read_line(&mut line).map_err(|err| err.to_string())? == 0 {
+            println!();
+            return Ok(());
+        }
+        match session.enter(line.trim_end_matches(['\r', '\n'])) {
+            Some(output) => print!("{}", output),
+            None => return Ok(()),
+        }
+    }
+}
 
-    let (s2, len) = calculate_length(s1);
+fn serve(port: u16) -> Result<(), String> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).map_err(|err| format!("can't listen on port {}: {}", port, err))?;
+    println!("the playground is on http://127.0.0.1:{}/?lesson=references_and_borrowing::fail", port);
+    println!("press Ctrl+C to stop it");
+    playground::serve(listener, env::temp_dir()).map_err(|err| err.to_string())
+}
 
-    println!("The length of '{}' is {}.", s2, len);
+fn explain_codes() -> Result<(), String> {
+    for code in explain::codes() {
+        println!("    {}  {}", code, explain::meaning(code).unwrap_or(""));
+    }
+    println!();
+    println!("see `rust-ownership explain <code>` for the examples");
+    Ok(())
 }
 
-fn calculate_length(s: String) -> (String, usize) {
-    let length = s.len(); // len() returns the length of a String
+fn explain(code: &str) -> Result<(), String> {
+    let dir = rustc::temp_dir("explain");
+    let explanation = explain::explain(code, &dir);
+    let _ = fs::remove_dir_all(&dir);
+    let explanation = explanation.map_err(|err| format!("can't run rustc: {}", err))?;
+    let explanation = explanation.ok_or_else(|| {
+        format!("no example fails with `{}`, try `rustc --explain {}`; the examples fail with {}", code, code, explain::codes().join(", "))
+    })?;
+    println!("{}", explanation);
+    Ok(())
+}
 
-    (s, length)
+fn check() -> Result<(), String> {
+    let dir = rustc::temp_dir("check");
+    let mut stale = 0;
+
+    for example in compile_fail::EXAMPLES {
+        let report = example.check(&dir).map_err(|err| format!("can't run rustc: {}", err))?;
+        if !report.is_ok() {
+            stale += 1;
+        }
+        println!("{}", report);
+    }
+    let _ = fs::remove_dir_all(&dir);
+
+    if stale > 0 {
+        return Err(format!("{} of {} examples need their comments updated", stale, compile_fail::EXAMPLES.len()));
+    }
+    Ok(())
 }
-// But this is too much ceremony and a lot of work for a concept that should be common. Luckily for us, Rust has a feature for this concept, called references.
+
@@ -0,0 +1,132 @@
+// Lesson Manifest ---
+// Everything the binary knows about the examples, as JSON for tools that want the lessons without reading Rust:
+// every function of the chapter modules, where it is, under which heading, and what happens when it is built and run.
+// The functions that aren't lessons themselves, like `change_two` which `main_five` calls, are listed too.
+
+use std::io;
+
+use crate::book;
+use crate::compile_fail;
+use crate::json::Value;
+use crate::lesson::{self, Chapter};
+
+/// Bumped when a field changes meaning or goes away, not when one is added.
+pub const VERSION: usize = 1;
+
+/// One function of a chapter module, or one example that doesn't compile.
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    /// `module::function`, as `lesson::find` takes it.
+    pub id: String,
+    pub module: &'static str,
+    pub function: String,
+    pub chapter: &'static str,
+    pub section: String,
+    /// The title of the lesson, `None` for the functions that are only called by lessons.
+    pub title: Option<&'static str>,
+    pub compiles: bool,
+    /// The error code an example that doesn't compile fails with, and rustc's message.
+    pub expected_error: Option<(String, String)>,
+    /// What the lesson prints, `None` if it can't run on its own.
+    pub stdout: Option<String>,
+    /// The file the function is in, relative to the root of the crate.
+    pub file: String,
+    /// The first and the last line of the function, counting from 1.
+    pub lines: (usize, usize),
+}
+
+/// The entries of every chapter, in the order they are written, each lesson run with `run` to get what it prints.
+///
+/// The binary passes `lesson::run_captured`, which runs the lessons in a child process.
+pub fn entries(run: impl Fn(&str) -> io::Result<String>) -> io::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    for chapter in lesson::CHAPTERS.iter() {
+        for (function, section) in functions(chapter) {
+            let id = format!("{}::{}", chapter.module, function);
+            let lesson = chapter.lessons.iter().find(|lesson| lesson.name == function);
+            let example = compile_fail::find(&id);
+
+            let stdout = match lesson {
+                Some(lesson) if lesson.run.is_some() => Some(run(&id)?),
+                _ => None,
+            };
+            let expected_error = example
+                .and_then(|example| compile_fail::annotations(example.source).into_iter().next())
+                .map(|annotation| (annotation.code, annotation.message));
+
+            entries.push(Entry {
+                module: chapter.module,
+                chapter: chapter.title,
+                section: lesson.map_or(section, |lesson| lesson.section.to_string()),
+                title: lesson.map(|lesson| lesson.title),
+                compiles: example.is_none(),
+                expected_error,
+                stdout,
+                file: example.map_or_else(|| format!("src/{}.rs", chapter.module), |example| example.path.to_string()),
+                lines: lesson::function_lines(&id).unwrap_or_default(),
+                function,
+                id,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// The names of the functions of a chapter, with the `mod` of each example that doesn't compile, in the order they
+/// are written, and the heading each one is under.
+pub fn functions(chapter: &Chapter) -> Vec<(String, String)> {
+    let mut section = chapter.title.to_string();
+    let mut functions = Vec::new();
+
+    for line in chapter.source.lines().take_while(|line| !line.starts_with("pub const CHAPTER")) {
+        if let Some((title, true)) = line.trim_start().strip_prefix("//").and_then(|comment| book::heading(comment.trim())) {
+            section = title.to_string();
+        }
+
+        let definition = line.strip_prefix("pub ").unwrap_or(line);
+        let name = match (definition.strip_prefix("fn "), line.strip_prefix("mod ")) {
+            (Some(rest), _) => rest.split(|c: char| c == '(' || c.is_whitespace()).next(),
+            (_, Some(rest)) => rest.strip_suffix(';'),
+            _ => None,
+        };
+        if let Some(name) = name {
+            functions.push((name.to_string(), section.clone()));
+        }
+    }
+    functions
+}
+
+impl Entry {
+    pub fn to_json(&self) -> Value {
+        let (code, message) = match &self.expected_error {
+            Some((code, message)) => (Some(code.as_str()), Some(message.as_str())),
+            None => (None, None),
+        };
+        Value::Object(vec![
+            ("id".to_string(), self.id.as_str().into()),
+            ("module".to_string(), self.module.into()),
+            ("function".to_string(), self.function.as_str().into()),
+            ("chapter".to_string(), self.chapter.into()),
+            ("section".to_string(), self.section.as_str().into()),
+            ("title".to_string(), self.title.into()),
+            ("lesson".to_string(), self.title.is_some().into()),
+            ("compiles".to_string(), self.compiles.into()),
+            ("expected_error".to_string(), code.into()),
+            ("expected_message".to_string(), message.into()),
+            ("stdout".to_string(), self.stdout.as_deref().into()),
+            ("file".to_string(), self.file.as_str().into()),
+            (
+                "lines".to_string(),
+                Value::Object(vec![("start".to_string(), self.lines.0.into()), ("end".to_string(), self.lines.1.into())]),
+            ),
+        ])
+    }
+}
+
+/// The whole manifest: `{"version": 1, "entries": [...]}`.
+pub fn to_json(entries: &[Entry]) -> Value {
+    Value::Object(vec![
+        ("version".to_string(), VERSION.into()),
+        ("entries".to_string(), Value::Array(entries.iter().map(Entry::to_json).collect())),
+    ])
+}
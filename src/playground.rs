@@ -0,0 +1,448 @@
+// Playground ---
+// A page to edit and run the examples, served on localhost so it works without reaching the public playground.
+// The code is built and run with the local rustc, in a directory of its own for each request.
+//
+// Anything that can reach the server can run code on this machine, so it only listens on 127.0.0.1, only answers
+// requests sent to a local host name, and only runs code sent with the `RUN_HEADER` header: a web page of another
+// origin can't add that header without asking first, and the server never says yes.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::compile_fail;
+use crate::json::Value;
+use crate::lesson;
+use crate::manifest;
+use crate::rustc;
+
+/// The port `serve` listens on when it isn't given one.
+pub const DEFAULT_PORT: u16 = 7878;
+/// The header a request to run code must have, with `run` as its value.
+pub const RUN_HEADER: &str = "x-rust-ownership";
+/// How long a program can run before it is killed.
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+// Larger requests are refused without being read.
+const MAX_BODY: usize = 100 * 1024;
+
+// Programs ---
+
+/// The lesson as a program that can be built on its own: the lesson, the functions of its chapter it calls,
+/// and a `main` that calls it the way `rust-ownership run` does.
+/// `None` for the lessons that use something of this crate, like `drop_trace`, which a lone file doesn't have.
+pub fn program(id: &str) -> Option<String> {
+    let (chapter, lesson) = lesson::find(id)?;
+    let mut program = format!("{}\n", lesson::function_source(id)?);
+
+    // An example that doesn't compile is a file of its own, it has everything it needs.
+    if compile_fail::find(id).is_none() {
+        let functions: Vec<String> = manifest::functions(chapter)
+            .into_iter()
+            .map(|(name, _)| name)
+            .filter(|name| compile_fail::find(&format!("{}::{}", chapter.module, name)).is_none())
+            .collect();
+        let mut added = vec![lesson.name.to_string()];
+        let mut i = 0;
+        while i < added.len() {
+            let code = lesson::strip_comments(&lesson::function_source(&format!("{}::{}", chapter.module, added[i])).unwrap_or_default());
+            for name in &functions {
+                if name != "main" && !added.contains(name) && calls(&code, name) {
+                    program.push('\n');
+                    program.push_str(&lesson::function_source(&format!("{}::{}", chapter.module, name))?);
+                    program.push('\n');
+                    added.push(name.clone());
+                }
+            }
+            i += 1;
+        }
+
+        // `use crate::...` lines, for the names this program can't have.
+        let imported = chapter
+            .source
+            .lines()
+            .filter_map(|line| line.strip_prefix("use crate::"))
+            .flat_map(|path| path.trim_end_matches(';').split(|c: char| !(c.is_alphanumeric() || c == '_')))
+            .filter(|name| !name.is_empty() && !functions.iter().any(|function| function == name));
+        let code = lesson::strip_comments(&program);
+        if imported.clone().any(|name| code.split(|c: char| !(c.is_alphanumeric() || c == '_')).any(|word| word == name)) {
+            return None;
+        }
+    }
+
+    if lesson.name != "main" {
+        let _ = write!(program, "\nfn main() {{\n    {};\n}}\n", run_expression(chapter.source, lesson.name));
+    }
+    Some(program)
+}
+
+// Whether `code` calls the function `name`.
+fn calls(code: &str, name: &str) -> bool {
+    let call = format!("{}(", name);
+    code.match_indices(&call).any(|(i, _)| !code[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_'))
+}
+
+// What the `run` of a lesson does, as written in its `Lesson`: `name()`, or the body of its closure.
+fn run_expression(source: &str, name: &str) -> String {
+    let call = format!("{}()", name);
+    let registry = match source.find("pub const CHAPTER").and_then(|start| {
+        let lesson = source[start..].find(&format!("name: \"{}\"", name))?;
+        Some(&source[start + lesson..])
+    }) {
+        Some(registry) => registry,
+        None => return call,
+    };
+
+    let closure = match registry.find("run: ").and_then(|run| registry[run..].strip_prefix("run: Some(||")) {
+        Some(closure) => closure.trim_start(),
+        None => return call,
+    };
+
+    // The closure ends at the parenthesis that closes `Some(`, not counting the ones in string literals.
+    let (mut depth, mut in_string, mut escaped) = (0, false, false);
+    for (i, c) in closure.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string && depth == 0 => return closure[..i].trim().to_string(),
+            ')' if !in_string => depth -= 1,
+            _ => {}
+        }
+    }
+    call
+}
+
+// HTTP ---
+
+/// A request, with only what the playground looks at.
+#[derive(Debug, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    /// Header names are lowercase.
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Request {
+    /// Reads a request, and its body if it has a `Content-Length`.
+    pub fn read(stream: &mut impl BufRead) -> io::Result<Request> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+        let mut line = String::new();
+        stream.read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (method, target) = match (parts.next(), parts.next()) {
+            (Some(method), Some(target)) => (method.to_string(), target.to_string()),
+            _ => return Err(invalid("not an HTTP request")),
+        };
+
+        let mut headers = Vec::new();
+        loop {
+            line.clear();
+            if stream.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+            }
+        }
+
+        let length = match headers.iter().find(|(name, _)| name == "content-length") {
+            Some((_, length)) => length.parse().map_err(|_| invalid("bad Content-Length"))?,
+            None => 0,
+        };
+        if length > MAX_BODY {
+            return Err(invalid("the request is too large"));
+        }
+        let mut body = vec![0; length];
+        stream.read_exact(&mut body)?;
+
+        let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+        let query = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (decode(name), decode(value))
+            })
+            .collect();
+
+        Ok(Request { method, path: decode(path), query, headers, body: String::from_utf8_lossy(&body).into_owned() })
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(header, _)| header == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query.iter().find(|(param, _)| param == name).map(|(_, value)| value.as_str())
+    }
+}
+
+// Percent-decoding, with `+` for a space as forms send it.
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+impl Response {
+    fn new(status: u16, content_type: &'static str, body: String) -> Response {
+        Response { status, content_type, body }
+    }
+
+    fn json(status: u16, value: Value) -> Response {
+        Response::new(status, "application/json", format!("{}\n", value))
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response::json(status, Value::Object(vec![("error".to_string(), message.into())]))
+    }
+
+    pub fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Internal Server Error",
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason,
+            self.content_type,
+            self.body.len(),
+            self.body
+        )?;
+        stream.flush()
+    }
+}
+
+/// Answers a request, building and running code in a new directory under `dir`.
+pub fn handle(request: &Request, dir: &Path) -> Response {
+    // A page on the internet can make the browser send a request to a name that resolves to 127.0.0.1.
+    let host = request.header("host").unwrap_or("");
+    let name = host.rsplit_once(':').map_or(host, |(name, _)| name);
+    if !["127.0.0.1", "localhost", "[::1]"].contains(&name) {
+        return Response::error(403, "only requests to localhost are answered");
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/") => {
+            let id = request.param("lesson").unwrap_or("references_and_borrowing::fail");
+            match program(id) {
+                Some(code) => Response::new(200, "text/html", page(id, &code)),
+                None => Response::new(404, "text/html", page(id, &format!("// there is no lesson `{}` that can run on its own\n", id))),
+            }
+        }
+        ("GET", "/lesson") => match request.param("id").and_then(|id| Some((id, program(id)?))) {
+            Some((id, code)) => Response::json(200, Value::Object(vec![("id".to_string(), id.into()), ("code".to_string(), code.into())])),
+            None => Response::error(404, "no such lesson, or it can't run on its own"),
+        },
+        ("POST", "/run") if request.header(RUN_HEADER) != Some("run") => {
+            Response::error(403, &format!("code is only run with the `{}: run` header", RUN_HEADER))
+        }
+        ("POST", "/run") => run(&request.body, dir),
+        (_, "/" | "/lesson" | "/run") => Response::error(405, "method not allowed"),
+        _ => Response::error(404, "not found"),
+    }
+}
+
+// Builds and runs `code`, and tells what rustc said and what the program printed.
+fn run(code: &str, dir: &Path) -> Response {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let name = format!("rust-ownership-serve-{}-{}", process::id(), RUNS.fetch_add(1, Ordering::Relaxed));
+    let dir = BuildDir(dir.join(name));
+
+    let result = rustc::run(code, "playground", &dir.0, TIMEOUT);
+    drop(dir);
+    let run = match result {
+        Ok(run) => run,
+        Err(err) => return Response::error(500, &format!("can't run rustc: {}", err)),
+    };
+
+    let diagnostics = run
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| !diagnostic.message.starts_with("aborting due to"))
+        .map(|diagnostic| {
+            Value::Object(vec![
+                ("level".to_string(), diagnostic.level.as_str().into()),
+                ("code".to_string(), diagnostic.code.as_deref().into()),
+                ("message".to_string(), diagnostic.message.as_str().into()),
+                ("line".to_string(), diagnostic.line.into()),
+                ("rendered".to_string(), diagnostic.rendered.as_str().into()),
+            ])
+        })
+        .collect();
+
+    let output = run.output.as_ref();
+    Response::json(
+        200,
+        Value::Object(vec![
+            ("built".to_string(), output.is_some().into()),
+            ("success".to_string(), output.is_some_and(|output| output.status == Some(0)).into()),
+            ("diagnostics".to_string(), Value::Array(diagnostics)),
+            ("stdout".to_string(), output.map(|output| output.stdout.as_str()).into()),
+            ("stderr".to_string(), output.map(|output| output.stderr.as_str()).into()),
+            ("status".to_string(), output.and_then(|output| output.status).map(|status| Value::Number(status as f64)).into()),
+            ("timed_out".to_string(), output.is_some_and(|output| output.timed_out).into()),
+        ]),
+    )
+}
+
+// The directory of one request, removed with everything in it once the request is done, even if it panics.
+struct BuildDir(PathBuf);
+
+impl Drop for BuildDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Answers the connections on `listener` until it fails, each on a thread of its own.
+pub fn serve(listener: TcpListener, dir: PathBuf) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let dir = dir.clone();
+        thread::spawn(move || {
+            let _ = connection(stream, &dir);
+        });
+    }
+    Ok(())
+}
+
+fn connection(mut stream: TcpStream, dir: &Path) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    let response = match Request::read(&mut BufReader::new(&stream)) {
+        Ok(request) => handle(&request, dir),
+        Err(err) => Response::error(400, &err.to_string()),
+    };
+    response.write_to(&mut stream)
+}
+
+// The Page ---
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn page(id: &str, code: &str) -> String {
+    let mut options = String::new();
+    for (chapter, lesson) in lesson::all() {
+        let lesson_id = chapter.id(lesson);
+        if program(&lesson_id).is_some() {
+            let selected = if lesson_id == id { " selected" } else { "" };
+            let _ = writeln!(options, "<option value=\"{0}\"{1}>{0}: {2}</option>", escape(&lesson_id), selected, escape(lesson.title));
+        }
+    }
+    // The code goes in last, whatever it has in it stays as it is.
+    PAGE.replace("{header}", RUN_HEADER).replace("{options}", &options).replace("{code}", &escape(code))
+}
+
+const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Ownership Playground</title>
+<style>
+body { margin: 1em; font-family: sans-serif; }
+header { display: flex; gap: 0.5em; margin-bottom: 0.5em; }
+select { flex: 1; }
+textarea, pre { width: 100%; box-sizing: border-box; font-family: Menlo, Consolas, monospace; font-size: 14px; }
+textarea { height: 50vh; tab-size: 4; }
+pre { background: #f6f8fa; padding: 0.8em; white-space: pre-wrap; min-height: 2em; }
+.failed { color: #d73a49; }
+</style>
+</head>
+<body>
+<header>
+<select id="lesson" onchange="location.search = '?lesson=' + encodeURIComponent(this.value)">
+{options}</select>
+<button id="run" onclick="run()">Run (Ctrl+Enter)</button>
+</header>
+<textarea id="code" spellcheck="false">{code}</textarea>
+<h3>rustc</h3>
+<pre id="diagnostics"></pre>
+<h3>Output</h3>
+<pre id="output"></pre>
+<script>
+const code = document.getElementById("code");
+const diagnostics = document.getElementById("diagnostics");
+const output = document.getElementById("output");
+
+async function run() {
+    diagnostics.textContent = "building...";
+    output.textContent = "";
+    output.className = "";
+    try {
+        const response = await fetch("/run", { method: "POST", headers: { "{header}": "run" }, body: code.value });
+        const result = await response.json();
+        if (result.error) {
+            diagnostics.textContent = result.error;
+            return;
+        }
+        diagnostics.textContent = result.diagnostics.map(d => d.rendered).join("") || "no errors, no warnings";
+        if (!result.built) {
+            output.textContent = "(it doesn't compile)";
+        } else {
+            output.textContent = result.stdout + result.stderr;
+            if (result.timed_out) output.textContent += "\n(killed after running too long)";
+            else if (!result.success) output.textContent += "\n(exited with " + result.status + ")";
+            output.className = result.success ? "" : "failed";
+        }
+    } catch (error) {
+        diagnostics.textContent = "can't reach the server: " + error;
+    }
+}
+
+code.addEventListener("keydown", event => {
+    if (event.key === "Enter" && (event.ctrlKey || event.metaKey)) {
+        event.preventDefault();
+        run();
+    } else if (event.key === "Tab") {
+        event.preventDefault();
+        code.setRangeText("    ", code.selectionStart, code.selectionEnd, "end");
+    }
+});
+</script>
+</body>
+</html>
+"#;
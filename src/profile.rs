@@ -0,0 +1,61 @@
+// Trainee Profiles ---
+// What a trainee did is kept in JSON files of their own, in `~/.rust-ownership/<user>/`,
+// so that several people can share a machine, and one person can stop and come back later.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::json;
+
+/// Overrides `~/.rust-ownership` as the directory that holds a folder per user.
+pub const HOME_ENV: &str = "RUST_OWNERSHIP_HOME";
+/// Overrides the user name, which is otherwise the login name.
+pub const USER_ENV: &str = "RUST_OWNERSHIP_USER";
+
+pub struct Profile {
+    pub user: String,
+    /// Where the files of this user are.
+    pub dir: PathBuf,
+}
+
+impl Profile {
+    pub fn new(user: &str, base: impl Into<PathBuf>) -> Profile {
+        Profile { user: user.to_string(), dir: base.into().join(user) }
+    }
+
+    /// The profile of whoever runs the binary, see `HOME_ENV` and `USER_ENV`.
+    pub fn current() -> io::Result<Profile> {
+        let user = [USER_ENV, "USER", "USERNAME"]
+            .iter()
+            .find_map(|name| env::var(name).ok().filter(|user| !user.is_empty()))
+            .unwrap_or_else(|| "default".to_string());
+        if user.contains(['/', '\\']) || user.starts_with('.') {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("`{}` can't be used as a user name", user)));
+        }
+
+        let base = match env::var_os(HOME_ENV) {
+            Some(base) => PathBuf::from(base),
+            None => env::var_os("HOME")
+                .or_else(|| env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".rust-ownership"))
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no home directory, set {}", HOME_ENV)))?,
+        };
+        Ok(Profile::new(&user, base))
+    }
+
+    /// Reads one of the files of the profile, `Null` if there is none yet.
+    pub fn load(&self, file: &str) -> io::Result<json::Value> {
+        match fs::read_to_string(self.dir.join(file)) {
+            Ok(text) => json::parse(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(json::Value::Null),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, file: &str, value: &json::Value) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(file), format!("{}\n", value))
+    }
+}
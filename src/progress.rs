@@ -0,0 +1,126 @@
+// Lesson Progress ---
+// The chapters are read over several sessions, so what a trainee did with each lesson is kept in their profile:
+// whether they read it with `show`, ran it with `run`, and got its "will this compile?" question right in the quiz.
+// `next` picks up after the last lesson they looked at.
+
+use crate::json::Value;
+use crate::lesson::{self, Chapter, Lesson};
+
+/// The file of a profile the progress is kept in.
+pub const PROGRESS_FILE: &str = "progress.json";
+
+/// Something a trainee can do with a lesson.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    Viewed,
+    Run,
+    /// The question about the lesson was answered right.
+    Passed,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Progress {
+    /// Only the lessons something was done with, in the order they were first seen.
+    pub lessons: Vec<LessonProgress>,
+    /// The id of the lesson viewed or run last, where `next` starts looking.
+    pub last: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct LessonProgress {
+    pub id: String,
+    pub viewed: bool,
+    pub run: bool,
+    pub passed: bool,
+}
+
+impl LessonProgress {
+    /// A lesson is done once it was read and run; the quiz is extra.
+    pub fn is_done(&self) -> bool {
+        self.viewed && self.run
+    }
+}
+
+impl Progress {
+    /// Reads progress written by `to_json`. Anything else gives empty progress.
+    pub fn from_json(value: &Value) -> Progress {
+        let lessons = value
+            .get("lessons")
+            .and_then(Value::as_array)
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|lesson| {
+                let flag = |name| lesson.get(name).and_then(Value::as_bool).unwrap_or(false);
+                Some(LessonProgress {
+                    id: lesson.get("id")?.as_str()?.to_string(),
+                    viewed: flag("viewed"),
+                    run: flag("run"),
+                    passed: flag("passed"),
+                })
+            })
+            .collect();
+        let last = value.get("last").and_then(Value::as_str).map(str::to_string);
+        Progress { lessons, last }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let lessons = self
+            .lessons
+            .iter()
+            .map(|lesson| {
+                Value::Object(vec![
+                    ("id".to_string(), lesson.id.as_str().into()),
+                    ("viewed".to_string(), lesson.viewed.into()),
+                    ("run".to_string(), lesson.run.into()),
+                    ("passed".to_string(), lesson.passed.into()),
+                ])
+            })
+            .collect();
+        Value::Object(vec![("lessons".to_string(), Value::Array(lessons)), ("last".to_string(), self.last.clone().into())])
+    }
+
+    pub fn record(&mut self, id: &str, step: Step) {
+        let index = match self.lessons.iter().position(|lesson| lesson.id == id) {
+            Some(index) => index,
+            None => {
+                self.lessons.push(LessonProgress { id: id.to_string(), ..LessonProgress::default() });
+                self.lessons.len() - 1
+            }
+        };
+
+        let lesson = &mut self.lessons[index];
+        match step {
+            Step::Viewed => lesson.viewed = true,
+            Step::Run => lesson.run = true,
+            Step::Passed => lesson.passed = true,
+        }
+        if step != Step::Passed {
+            self.last = Some(id.to_string());
+        }
+    }
+
+    /// What was done with a lesson, all `false` if nothing was.
+    pub fn of(&self, id: &str) -> LessonProgress {
+        match self.lessons.iter().find(|lesson| lesson.id == id) {
+            Some(lesson) => LessonProgress { id: lesson.id.clone(), ..*lesson },
+            None => LessonProgress { id: id.to_string(), ..LessonProgress::default() },
+        }
+    }
+
+    /// The first lesson after the last one looked at that isn't done, in reading order,
+    /// going back to the start for the ones skipped. `None` once every lesson is done.
+    pub fn next(&self) -> Option<(&'static Chapter, &'static Lesson)> {
+        let lessons: Vec<_> = lesson::all().collect();
+        let start = self
+            .last
+            .as_deref()
+            .and_then(|last| lessons.iter().position(|(chapter, lesson)| chapter.id(lesson) == last))
+            .map_or(0, |i| i + 1);
+
+        lessons[start..]
+            .iter()
+            .chain(&lessons[..start])
+            .find(|(chapter, lesson)| !self.of(&chapter.id(lesson)).is_done())
+            .copied()
+    }
+}
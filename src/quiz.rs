@@ -0,0 +1,375 @@
+// Quiz and Exercises ---
+// Reading about the rules is not the same as applying them. Every topic of the chapters gets two kinds of exercises:
+// "will this compile?" questions, made from the examples themselves, and fill-in-the-blank tasks that the local rustc builds and runs.
+// What each trainee got right is kept in their profile, see `profile`.
+
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::compile_fail;
+use crate::json::Value;
+use crate::lesson;
+use crate::rustc::{self, Diagnostic};
+
+/// A part of the chapters, made of the `// Section ---` headings that teach it.
+pub struct Topic {
+    pub name: &'static str,
+    pub sections: &'static [&'static str],
+}
+
+pub const TOPICS: &[Topic] = &[
+    Topic { name: "Ownership Rules", sections: &["Ownership Rules", "Variable Scope", "The String Type"] },
+    Topic {
+        name: "Move",
+        sections: &["Ways Variables and Data Interact: Move", "Ownership and Functions", "Return Values and Scope"],
+    },
+    Topic { name: "Clone", sections: &["Ways Variables and Data Interact: Clone"] },
+    Topic { name: "Copy", sections: &["Stack-Only Data: Copy"] },
+    Topic { name: "References", sections: &["References and Borrowing"] },
+    Topic { name: "Mutable References", sections: &["Mutable References"] },
+    Topic { name: "Dangling References", sections: &["Dangling References"] },
+    Topic {
+        name: "Slices",
+        sections: &["Without Slices", "String Slices", "String Literals Are Slices", "String Slices as Parameters", "Other Slices"],
+    },
+];
+
+/// Finds a topic by name, ignoring case, with `-` for spaces: `mutable-references`.
+pub fn topic(name: &str) -> Option<&'static Topic> {
+    TOPICS.iter().find(|topic| topic.slug() == name.to_lowercase().replace(' ', "-"))
+}
+
+impl Topic {
+    pub fn slug(&self) -> String {
+        self.name.to_lowercase().replace(' ', "-")
+    }
+
+    /// A "will this compile?" question for every example of the topic.
+    pub fn questions(&self) -> Vec<Question> {
+        lesson::all()
+            .filter(|(_, lesson)| self.sections.contains(&lesson.section))
+            .filter_map(|(chapter, lesson)| Question::about(&chapter.id(lesson)))
+            .collect()
+    }
+
+    pub fn tasks(&self) -> impl Iterator<Item = &'static Task> + '_ {
+        TASKS.iter().filter(move |task| task.topic == self.name)
+    }
+}
+
+// Will It Compile? ---
+
+/// The errors the chapters show, which are the wrong answers to every question.
+pub const ERRORS: &[(&str, &str)] = &[
+    ("E0382", "a value is used after it was moved"),
+    ("E0499", "a value is borrowed as mutable more than once at a time"),
+    ("E0502", "a value is borrowed as mutable while it is also borrowed as immutable"),
+    ("E0106", "a returned reference has nothing to borrow from"),
+    ("E0596", "a value is changed through a `&` reference"),
+];
+
+pub struct Question {
+    /// `compiles:` and the id of the lesson the code comes from.
+    pub id: String,
+    pub lesson: String,
+    /// The code of the example without its comments, which often give the answer away.
+    pub code: String,
+    /// The choices in the order they are shown: "it compiles", then an error for each of `ERRORS`.
+    pub choices: Vec<String>,
+    /// The index of the right choice.
+    pub answer: usize,
+}
+
+impl Question {
+    pub fn about(lesson: &str) -> Option<Question> {
+        let source = lesson::function_source(lesson)?;
+        let answer = match compile_fail::find(lesson) {
+            None => 0,
+            Some(example) => {
+                let code = *example.expected_codes().first()?;
+                1 + ERRORS.iter().position(|&(known, _)| known == code)?
+            }
+        };
+
+        let mut choices = vec!["yes, it compiles".to_string()];
+        choices.extend(ERRORS.iter().map(|(code, meaning)| format!("no, error[{}]: {}", code, meaning)));
+
+        Some(Question { id: format!("compiles:{}", lesson), lesson: lesson.to_string(), code: lesson::strip_comments(&source), choices, answer })
+    }
+
+    /// Whether `answer`, a choice number counting from 1, is the right one.
+    pub fn grade(&self, answer: &str) -> Option<bool> {
+        let choice: usize = answer.trim().parse().ok()?;
+        (1..=self.choices.len()).contains(&choice).then_some(choice - 1 == self.answer)
+    }
+}
+
+// Fill In The Blank ---
+
+/// Where the answer goes in a task.
+pub const BLANK: &str = "___";
+
+pub struct Task {
+    pub id: &'static str,
+    pub topic: &'static str,
+    pub prompt: &'static str,
+    /// A program with one `BLANK`, that compiles once it is filled in right.
+    pub template: &'static str,
+    /// What the program prints once it is filled in right.
+    pub output: &'static str,
+    /// An answer that passes, to check the task itself.
+    pub solution: &'static str,
+}
+
+pub const TASKS: &[Task] = &[
+    Task {
+        id: "scope",
+        topic: "Ownership Rules",
+        prompt: "Declare s, a String holding \"hello\", so that it is still valid where it is printed, and can be changed.",
+        template: r#"fn main() {
+    ___
+    {
+        s.push_str(", world!");
+    }
+    println!("{}", s);
+}
+"#,
+        output: "hello, world!\n",
+        solution: r#"let mut s = String::from("hello");"#,
+    },
+    Task {
+        id: "give_back",
+        topic: "Move",
+        prompt: "takes_and_gives_back took ownership of a String: give it back to the caller.",
+        template: r#"fn takes_and_gives_back(a_string: String) -> String {
+    ___
+}
+
+fn main() {
+    let s1 = String::from("hello");
+    let s2 = takes_and_gives_back(s1);
+    println!("{}", s2);
+}
+"#,
+        output: "hello\n",
+        solution: "a_string",
+    },
+    Task {
+        id: "clone",
+        topic: "Clone",
+        prompt: "Give s2 its own copy of the heap data, so that s1 is still valid.",
+        template: r#"fn main() {
+    let s1 = String::from("hello");
+    let s2 = ___;
+
+    println!("s1 = {}, s2 = {}", s1, s2);
+}
+"#,
+        output: "s1 = hello, s2 = hello\n",
+        solution: "s1.clone()",
+    },
+    Task {
+        id: "copy",
+        topic: "Copy",
+        prompt: "Pick a type for x, so that x is still valid after `let y = x;`.",
+        template: r#"fn main() {
+    let x: ___ = 5;
+    let y = x;
+
+    println!("x = {}, y = {}", x, y);
+}
+"#,
+        output: "x = 5, y = 5\n",
+        solution: "i32",
+    },
+    Task {
+        id: "borrow",
+        topic: "References",
+        prompt: "Pass s1 to calculate_length without giving it ownership.",
+        template: r#"fn calculate_length(s: &String) -> usize {
+    s.len()
+}
+
+fn main() {
+    let s1 = String::from("hello");
+    let len = calculate_length(___);
+
+    println!("The length of '{}' is {}.", s1, len);
+}
+"#,
+        output: "The length of 'hello' is 5.\n",
+        solution: "&s1",
+    },
+    Task {
+        id: "mutable_parameter",
+        topic: "Mutable References",
+        prompt: "Write the type of the parameter, so that change can modify the String it is given.",
+        template: r#"fn change(some_string: ___) {
+    some_string.push_str(", world");
+}
+
+fn main() {
+    let mut s = String::from("hello");
+    change(&mut s);
+    println!("{}", s);
+}
+"#,
+        output: "hello, world\n",
+        solution: "&mut String",
+    },
+    Task {
+        id: "no_dangle",
+        topic: "Dangling References",
+        prompt: "Write the return type, so that no_dangle doesn't return a reference to a String that is about to be dropped.",
+        template: r#"fn no_dangle() -> ___ {
+    let s = String::from("hello");
+
+    s
+}
+
+fn main() {
+    println!("{}", no_dangle());
+}
+"#,
+        output: "hello\n",
+        solution: "String",
+    },
+    Task {
+        id: "first_word",
+        topic: "Slices",
+        prompt: "Return the slice of s before the space.",
+        template: r#"fn first_word(s: &str) -> &str {
+    let bytes = s.as_bytes();
+
+    for (i, &item) in bytes.iter().enumerate() {
+        if item == b' ' {
+            return ___;
+        }
+    }
+
+    &s[..]
+}
+
+fn main() {
+    println!("{}", first_word("hello world"));
+}
+"#,
+        output: "hello\n",
+        solution: "&s[0..i]",
+    },
+];
+
+pub fn task(id: &str) -> Option<&'static Task> {
+    TASKS.iter().find(|task| task.id == id)
+}
+
+/// What rustc said about an answer, and what the program printed.
+pub struct Grade {
+    pub passed: bool,
+    /// The errors rustc reported, empty if the answer compiles.
+    pub errors: Vec<Diagnostic>,
+    /// What the program printed, `None` if it didn't build.
+    pub printed: Option<String>,
+}
+
+// The tasks print a line or two: one still running after this never stops.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+impl Task {
+    /// The id the results of the task are recorded under.
+    pub fn result_id(&self) -> String {
+        format!("task:{}", self.id)
+    }
+
+    /// The template with `answer` in the blank.
+    pub fn fill(&self, answer: &str) -> String {
+        self.template.replacen(BLANK, answer.trim(), 1)
+    }
+
+    /// Builds the filled in template with the local rustc in `dir`, and runs it: the answer passes
+    /// if the program prints what it should. Compiling is not enough, `&s[..]` compiles where `&s[0..i]` goes.
+    pub fn grade(&self, answer: &str, dir: &Path) -> io::Result<Grade> {
+        if answer.trim().is_empty() || answer.contains(BLANK) {
+            return Ok(Grade { passed: false, errors: Vec::new(), printed: None });
+        }
+
+        let run = rustc::run(&self.fill(answer), &format!("task_{}", self.id), dir, TIMEOUT)?;
+        let errors: Vec<Diagnostic> = run
+            .diagnostics
+            .into_iter()
+            .filter(|diagnostic| diagnostic.level == "error" && !diagnostic.message.starts_with("aborting due to"))
+            .collect();
+        let passed = run.output.as_ref().is_some_and(|output| output.status == Some(0) && output.stdout == self.output);
+        Ok(Grade { passed, errors, printed: run.output.map(|output| output.stdout) })
+    }
+}
+
+// Results ---
+
+/// The file of a profile the results are kept in.
+pub const RESULTS_FILE: &str = "results.json";
+
+/// How a trainee did on each exercise they tried.
+#[derive(Debug, Default, PartialEq)]
+pub struct Results {
+    pub exercises: Vec<Attempts>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Attempts {
+    /// The id of a question, or `task:` and the id of a task.
+    pub id: String,
+    pub tries: usize,
+    /// Whether one of the tries was right.
+    pub passed: bool,
+}
+
+impl Results {
+    /// Reads results written by `to_json`. Anything else gives empty results.
+    pub fn from_json(value: &Value) -> Results {
+        let exercises = value
+            .get("exercises")
+            .and_then(Value::as_array)
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|exercise| {
+                Some(Attempts {
+                    id: exercise.get("id")?.as_str()?.to_string(),
+                    tries: exercise.get("tries")?.as_u64()? as usize,
+                    passed: exercise.get("passed")?.as_bool()?,
+                })
+            })
+            .collect();
+        Results { exercises }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let exercises = self
+            .exercises
+            .iter()
+            .map(|exercise| {
+                Value::Object(vec![
+                    ("id".to_string(), exercise.id.as_str().into()),
+                    ("tries".to_string(), exercise.tries.into()),
+                    ("passed".to_string(), exercise.passed.into()),
+                ])
+            })
+            .collect();
+        Value::Object(vec![("exercises".to_string(), Value::Array(exercises))])
+    }
+
+    pub fn record(&mut self, id: &str, passed: bool) {
+        match self.exercises.iter_mut().find(|exercise| exercise.id == id) {
+            Some(exercise) => {
+                exercise.tries += 1;
+                exercise.passed |= passed;
+            }
+            None => self.exercises.push(Attempts { id: id.to_string(), tries: 1, passed }),
+        }
+    }
+
+    pub fn passed(&self, id: &str) -> bool {
+        self.exercises.iter().any(|exercise| exercise.id == id && exercise.passed)
+    }
+}
@@ -1,3 +1,5 @@
+use crate::lesson::{Chapter, Lesson};
+
 // References and Borrowing ---
 // The issue with the tuple code in the previous example is that we have to return the String to the calling function so we can still use the String after
 // the call to calculate_length, because the String was moved into calculate_length.
@@ -61,14 +63,9 @@ fn change_two(some_string: &mut String) {
 
 // But mutable references have one big RESTRICTION: you can have only one mutable reference to a particular piece of data in a particular scope.
 // This code will fail:
-fn fail() {
-    let mut s = String::from("hello");
-
-    let r1 = &mut s;
-    let r2 = &mut s; // error[E0499]: cannot borrow `s` as mutable more than once at a time
-
-    println!("{}, {}", r1, r2);
-}
+#[cfg(feature = "show-errors")]
+#[path = "../tests/compile-fail/references_and_borrowing/fail.rs"]
+mod fail;
 
 // This restriction allows for mutation but in a very controlled fashion.
 // It’s something that new Rustaceans struggle with, because most languages let you mutate whenever you’d like.
@@ -95,16 +92,9 @@ fn asd() {
 }
 
 // A similar rule exists for combining mutable and immutable references.
-fn dsa () {
-    let mut s = String::from("hello");
-
-    let r1 = &s; // no problem
-    let r2 = &s; // no problem
-    let r3 = &mut s; // BIG PROBLEM
-
-    println!("{}, {}, and {}", r1, r2, r3);
-}
-// error[E0502]: cannot borrow `s` as mutable because it is also borrowed as immutable
+#[cfg(feature = "show-errors")]
+#[path = "../tests/compile-fail/references_and_borrowing/dsa.rs"]
+mod dsa;
 
 // Whew! We also cannot have a mutable reference while we have an immutable one.
 // Users of an immutable reference don’t expect the values to suddenly change out from under them!
@@ -133,16 +123,9 @@ fn ddas() {
 
 // Let’s try to create a dangling reference, which Rust will prevent with a compile-time error:
 
-fn main_six() {
-    let reference_to_nothing = dangle();
-}
-
-// dangle returns a reference to a String
-fn dangle() -> &String { // error[E0106]: missing lifetime specifier, help: consider giving it a 'static lifetime: `&'static`
-    let s = String::from("hello"); // s is a new String
-
-    &s // we return a reference to the String, s
-} // Here, s goes out of scope, and is dropped. Its memory goes away. DANGER!
+#[cfg(feature = "show-errors")]
+#[path = "../tests/compile-fail/references_and_borrowing/dangle.rs"]
+mod dangle;
 
 // in other words: this function's return type contains a borrowed value, but there is no value for it to be borrowed from.
 
@@ -163,3 +146,25 @@ fn no_dangle() -> String {
 
 // - At any given time, you can have either one mutable reference or any number of immutable references.
 // - References must always be valid.
+
+pub const CHAPTER: Chapter = Chapter {
+    module: "references_and_borrowing",
+    source: include_str!("references_and_borrowing.rs"),
+    title: "References and Borrowing",
+    lessons: &[
+        Lesson { name: "main_three", section: "References and Borrowing", title: "Borrowing a String instead of taking ownership", run: Some(main_three) },
+        Lesson { name: "main_four", section: "References and Borrowing", title: "References are immutable by default", run: Some(main_four) },
+        Lesson { name: "main_five", section: "Mutable References", title: "Modifying a borrowed value with &mut", run: Some(main_five) },
+        Lesson { name: "fail", section: "Mutable References", title: "Two mutable references at the same time", run: None },
+        Lesson { name: "asd", section: "Mutable References", title: "A new scope allows another mutable reference", run: Some(asd) },
+        Lesson { name: "dsa", section: "Mutable References", title: "A mutable reference while immutable ones are in use", run: None },
+        Lesson { name: "ddas", section: "Mutable References", title: "A reference's scope ends at its last use", run: Some(ddas) },
+        Lesson { name: "dangle", section: "Dangling References", title: "Returning a reference to a local String", run: None },
+        Lesson {
+            name: "no_dangle",
+            section: "Dangling References",
+            title: "Returning the String itself",
+            run: Some(|| println!("{}", no_dangle())),
+        },
+    ],
+};
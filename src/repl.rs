@@ -0,0 +1,299 @@
+// Ownership REPL ---
+// Type statements one at a time and see what happens to each value right away, without an edit and compile loop.
+// Every statement is added to a buffer that is simulated again from the start, so borrows see every line written so far,
+// and only what happens from the new statement on is told. A statement that breaks a rule is told why and rolled back.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::diagram::Snapshot;
+use crate::lesson;
+use crate::simulator::{self, Liveness, Scenario};
+
+pub const HELP: &str = "\
+type a statement, like `let s1 = String::from(\"hi\")`, `let s2 = s1` or `print s1`;
+the `;` at the end is optional, and a `fn` or a block can span several lines
+
+commands:
+    :load <scenario>  start over with the statements of a scenario, like `bye` or `dsa`
+    :scenarios        list the scenarios
+    :diagram          draw the stack and the heap after the last statement
+    :end              tell what happens when the variables go out of scope at the end
+    :list             print the buffer, with the line numbers the messages refer to
+    :undo             remove the last statement
+    :reset            start over with an empty buffer
+    :lexical          switch between non-lexical lifetimes (the default) and lexical ones
+    :help             print this
+    :quit             leave";
+
+/// The statements and functions entered so far, and what simulating them told.
+pub struct Session {
+    entries: Vec<Entry>,
+    liveness: Liveness,
+    snapshot: Option<Snapshot>,
+    /// The lines of a statement that isn't finished yet, with unclosed braces.
+    pending: Vec<String>,
+}
+
+struct Entry {
+    code: String,
+    /// A `fn`, which goes after the statements so that their line numbers start at 1.
+    function: bool,
+}
+
+impl Default for Session {
+    fn default() -> Session {
+        Session::new()
+    }
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session { entries: Vec::new(), liveness: Liveness::NonLexical, snapshot: None, pending: Vec::new() }
+    }
+
+    /// What to ask for the next line with, a different one in the middle of a statement.
+    pub fn prompt(&self) -> &'static str {
+        if self.pending.is_empty() {
+            ">> "
+        } else {
+            ".. "
+        }
+    }
+
+    /// Handles one line of input and returns what to print, every line of it ending with a newline, or `None` to quit.
+    pub fn enter(&mut self, line: &str) -> Option<String> {
+        if self.pending.is_empty() {
+            if let Some(command) = line.trim().strip_prefix(':') {
+                return self.command(command);
+            }
+        }
+        Some(self.line(line).1)
+    }
+
+    // A line of code, which finishes a statement unless it leaves braces open. `false` if the statement was rolled back.
+    fn line(&mut self, line: &str) -> (bool, String) {
+        self.pending.push(line.to_string());
+        let code = self.pending.join("\n");
+        if lesson::depth(&code) > 0 {
+            return (true, String::new());
+        }
+
+        self.pending.clear();
+        if code.lines().all(|line| lesson::split_comment(line).0.trim().is_empty()) {
+            return (true, String::new());
+        }
+        self.add(&code)
+    }
+
+    fn command(&mut self, command: &str) -> Option<String> {
+        let mut words = command.split_whitespace();
+        let output = match (words.next().unwrap_or(""), words.next()) {
+            ("quit", None) | ("q", None) => return None,
+            ("help", None) => HELP.to_string(),
+            ("load", Some(name)) => match simulator::scenario(name) {
+                Some(scenario) => self.load(scenario),
+                None => format!("no scenario named `{}`, see `:scenarios`", name),
+            },
+            ("scenarios", None) => {
+                let names: Vec<&str> = simulator::SCENARIOS.iter().map(|scenario| scenario.name).collect();
+                names.join(" ")
+            }
+            ("diagram", None) => match &self.snapshot {
+                Some(snapshot) if !self.entries.is_empty() => snapshot.to_string(),
+                _ => "nothing to draw yet".to_string(),
+            },
+            ("end", None) => self.end(),
+            ("list", None) => {
+                let mut listing = String::new();
+                for (i, line) in self.source().lines().enumerate() {
+                    let _ = writeln!(listing, "{:>3} | {}", i + 1, line);
+                }
+                listing
+            }
+            ("undo", None) => match self.entries.pop() {
+                Some(entry) => {
+                    self.rerun();
+                    format!("removed `{}`", entry.code.lines().next().unwrap_or_default().trim())
+                }
+                None => "nothing to undo".to_string(),
+            },
+            ("reset", None) => {
+                *self = Session { liveness: self.liveness, ..Session::new() };
+                "the buffer is empty".to_string()
+            }
+            ("lexical", None) => {
+                self.liveness = match self.liveness {
+                    Liveness::NonLexical => Liveness::Lexical,
+                    Liveness::Lexical => Liveness::NonLexical,
+                };
+                self.rerun();
+                match self.liveness {
+                    Liveness::Lexical => "borrows now last until the end of their scope, as before Rust 2018".to_string(),
+                    Liveness::NonLexical => "borrows now end after their last use, as rustc does".to_string(),
+                }
+            }
+            _ => format!("unknown command `:{}`, see `:help`", command.trim()),
+        };
+        if output.ends_with('\n') {
+            Some(output)
+        } else {
+            Some(output + "\n")
+        }
+    }
+
+    // Starts over with the functions of a scenario, then its statements one by one, stopping at the first one that fails.
+    fn load(&mut self, scenario: &Scenario) -> String {
+        *self = Session { liveness: self.liveness, ..Session::new() };
+        let mut output = String::new();
+        let (entry, functions) = match scenario.functions.split_first() {
+            Some(split) => split,
+            None => return output,
+        };
+
+        // The source of the entry function can have other functions after it, like `dangle` after `main_six`.
+        let (body, rest) = split_body(&lesson::function_source(entry).unwrap_or_default());
+        let sources = functions.iter().filter_map(|id| lesson::function_source(id));
+        for source in sources.chain(Some(Cow::from(rest.as_str())).filter(|rest| !rest.trim().is_empty())) {
+            let _ = writeln!(output, "{}", source.trim());
+            let (ok, told) = self.add(source.trim());
+            output.push_str(&told);
+            if !ok {
+                output.push_str("the rest of the scenario was not loaded\n");
+                return output;
+            }
+        }
+
+        for line in body.lines() {
+            let _ = writeln!(output, "{}{}", self.prompt(), line);
+            let (ok, told) = self.line(line);
+            output.push_str(&told);
+            if !ok {
+                output.push_str("the rest of the scenario was not loaded\n");
+                break;
+            }
+        }
+        output
+    }
+
+    // Simulates the buffer with `code` added, and keeps it if nothing went wrong.
+    fn add(&mut self, code: &str) -> (bool, String) {
+        self.entries.push(entry(code));
+        let source = self.source();
+
+        let trace = match simulator::simulate_with(&source, self.liveness) {
+            Ok(trace) => trace,
+            Err(err) => {
+                self.entries.pop();
+                return (false, format!("{}\nrolled back\n", err));
+            }
+        };
+
+        // What happens from the first event on a line of the new statement, calls included, until the end of the session.
+        let end = source.lines().count();
+        let lines = self.lines_of_last_statement();
+        let mut output = String::new();
+        let start = trace.events.iter().position(|event| lines.contains(&event.line)).unwrap_or(trace.events.len());
+        for event in trace.events[start..].iter().filter(|event| event.line != end) {
+            let _ = writeln!(output, "    {}", event.text);
+        }
+
+        if let Some(error) = trace.error {
+            self.entries.pop();
+            let _ = writeln!(output, "{}\nrolled back", error);
+            return (false, output);
+        }
+
+        self.snapshot = trace.steps.into_iter().last().map(|step| step.snapshot);
+        (true, output)
+    }
+
+    // Simulates the buffer again after it changed without a new statement.
+    fn rerun(&mut self) {
+        self.snapshot = simulator::simulate_with(&self.source(), self.liveness).ok().and_then(|mut trace| trace.steps.pop()).map(|step| step.snapshot);
+    }
+
+    // The lines the last entry has in `source`, if it is a statement.
+    fn lines_of_last_statement(&self) -> Range<usize> {
+        match self.entries.last() {
+            Some(last) if !last.function => {
+                let statements = self.entries.iter().filter(|entry| !entry.function);
+                let end = statements.map(|entry| entry.code.lines().count()).sum::<usize>() + 1;
+                end - last.code.lines().count()..end
+            }
+            _ => 0..0,
+        }
+    }
+
+    fn end(&self) -> String {
+        let source = self.source();
+        let end = source.lines().count();
+        let trace = match simulator::simulate_with(&source, self.liveness) {
+            Ok(trace) => trace,
+            Err(err) => return err.to_string(),
+        };
+
+        let mut output = String::new();
+        for event in trace.events.iter().filter(|event| event.line == end) {
+            let _ = writeln!(output, "    {}", event.text);
+        }
+        if output.is_empty() {
+            output.push_str("nothing to drop\n");
+        }
+        output
+    }
+
+    // The statements first, then the functions, then a last line where the variables of the session go out of scope.
+    // The empty block there makes sure the statements are what runs, even when there are none yet.
+    fn source(&self) -> String {
+        let statements = self.entries.iter().filter(|entry| !entry.function);
+        let functions = self.entries.iter().filter(|entry| entry.function);
+        let mut source = String::new();
+        for entry in statements.chain(functions) {
+            source.push_str(&entry.code);
+            source.push('\n');
+        }
+        source.push_str("{} // the end of the session");
+        source
+    }
+}
+
+// What the user typed, made into the code the simulator reads.
+fn entry(code: &str) -> Entry {
+    let trimmed = code.trim();
+    let first = code.lines().map(|line| lesson::split_comment(line).0.trim()).find(|line| !line.is_empty()).unwrap_or_default();
+    let function = first.starts_with("fn ") || first.starts_with("pub fn ");
+
+    // `print a, b` is short for `println!("{} {}", a, b);`.
+    if let Some(args) = trimmed.strip_prefix("print ") {
+        let args: Vec<&str> = args.trim_end_matches(';').split(',').map(str::trim).collect();
+        let placeholders = vec!["{}"; args.len()].join(" ");
+        return Entry { code: format!("println!(\"{}\", {});", placeholders, args.join(", ")), function };
+    }
+
+    // The `;` is optional on the last line, but it must go before a comment.
+    let mut lines: Vec<String> = code.lines().map(str::to_string).collect();
+    if let Some(last) = lines.iter_mut().rev().find(|line| !lesson::split_comment(line).0.trim().is_empty()) {
+        let (statement, comment) = lesson::split_comment(last);
+        let statement = statement.trim_end();
+        let needs_semicolon = !statement.ends_with(';') && (!statement.ends_with('}') || trimmed.starts_with("let "));
+        if needs_semicolon && !function {
+            *last = format!("{}; {}", statement, comment).trim_end().to_string();
+        }
+    }
+    Entry { code: lines.join("\n"), function }
+}
+
+// The body of the first function in `source`, dedented, and what comes after that function.
+fn split_body(source: &str) -> (String, String) {
+    let lines: Vec<&str> = source.lines().collect();
+    let (start, end) = match lines.iter().position(|line| line.starts_with("fn ") || line.starts_with("pub fn ")) {
+        Some(signature) => (signature + 1, lesson::end_of_function(&lines, signature)),
+        None => (0, lines.len()),
+    };
+
+    let body = lines.get(start..end).unwrap_or_default().iter().map(|line| line.strip_prefix("    ").unwrap_or(line)).collect::<Vec<_>>().join("\n");
+    let rest = lines.get(end + 1..).unwrap_or_default().join("\n");
+    (body, rest)
+}
@@ -0,0 +1,181 @@
+// Running The Compiler ---
+// Helpers to run the local rustc on a piece of code and read back what it said, using its JSON output.
+
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::json;
+
+/// The edition the crate is written in, which is also the one examples are compiled with.
+pub const EDITION: &str = "2018";
+
+/// One error, warning or note from rustc.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    /// `error`, `warning`, `note`, ...
+    pub level: String,
+    /// The error code, like `E0382`, if the diagnostic has one.
+    pub code: Option<String>,
+    pub message: String,
+    /// Line of the primary span, counting from 1.
+    pub line: Option<usize>,
+    /// Column the primary span starts at, in characters counting from 1.
+    pub column: Option<usize>,
+    /// The notes and help attached to the diagnostic, like `help: consider cloning the value`.
+    pub children: Vec<String>,
+    /// The diagnostic as rustc would print it to a terminal.
+    pub rendered: String,
+}
+
+/// A `Command` for the rustc that cargo uses, or the one on the `PATH`.
+pub fn command() -> Command {
+    Command::new(env::var_os("RUSTC").unwrap_or_else(|| "rustc".into()))
+}
+
+/// A directory for the files rustc writes, `rust-ownership-<purpose>-<pid>` in the temporary directory of the system.
+/// It is this process's own, so that two binaries running at once don't build over each other's files.
+pub fn temp_dir(purpose: &str) -> PathBuf {
+    env::temp_dir().join(format!("rust-ownership-{}-{}", purpose, process::id()))
+}
+
+/// Type-checks and borrow-checks `source` as a library crate named `name`, writing into `dir`,
+/// and returns every diagnostic rustc emitted.
+pub fn check(source: &str, name: &str, dir: &Path) -> io::Result<Vec<Diagnostic>> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.rs", name));
+    fs::write(&path, source)?;
+
+    let output = command()
+        .args(["--edition", EDITION, "--crate-type", "lib", "--emit", "metadata", "--error-format", "json"])
+        .arg("--crate-name")
+        .arg(name)
+        .arg("--out-dir")
+        .arg(dir)
+        .arg(&path)
+        .output()?;
+
+    parse(&String::from_utf8_lossy(&output.stderr))
+}
+
+/// What happened when a program was built and run, see `run`.
+#[derive(Debug)]
+pub struct Run {
+    pub diagnostics: Vec<Diagnostic>,
+    /// What the program did, `None` if it didn't build.
+    pub output: Option<Output>,
+}
+
+#[derive(Debug)]
+pub struct Output {
+    pub stdout: String,
+    pub stderr: String,
+    /// The exit code, `None` if the program was killed.
+    pub status: Option<i32>,
+    /// Whether it was killed for running longer than it was allowed to.
+    pub timed_out: bool,
+}
+
+// A program that prints in a loop is cut there.
+const MAX_OUTPUT: u64 = 64 * 1024;
+
+/// Builds `source` as a binary named `name` in `dir`, and runs it if it builds, for at most `timeout`.
+pub fn run(source: &str, name: &str, dir: &Path, timeout: Duration) -> io::Result<Run> {
+    fs::create_dir_all(dir)?;
+    let file = format!("{}.rs", name);
+    let binary = format!("{}{}", name, env::consts::EXE_SUFFIX);
+    fs::write(dir.join(&file), source)?;
+
+    // Built from inside `dir`, so that the errors point at `name.rs` rather than somewhere in a temporary directory.
+    let build = command()
+        .current_dir(dir)
+        .args(["--edition", EDITION, "--crate-type", "bin", "--error-format", "json"])
+        .arg("--crate-name")
+        .arg(name)
+        .arg("-o")
+        .arg(&binary)
+        .arg(&file)
+        .output()?;
+    let diagnostics = parse(&String::from_utf8_lossy(&build.stderr))?;
+    if !build.status.success() {
+        return Ok(Run { diagnostics, output: None });
+    }
+
+    let mut child = Command::new(dir.join(&binary)).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let stdout = read_pipe(child.stdout.take().expect("stdout is piped"));
+    let stderr = read_pipe(child.stderr.take().expect("stderr is piped"));
+
+    let start = Instant::now();
+    let (status, timed_out) = loop {
+        if let Some(status) = child.try_wait()? {
+            break (status.code(), false);
+        }
+        if start.elapsed() > timeout {
+            child.kill()?;
+            child.wait()?;
+            break (None, true);
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    let output = Output {
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+        status,
+        timed_out,
+    };
+    Ok(Run { diagnostics, output: Some(output) })
+}
+
+// Pipes are read on threads of their own, a program that fills one would wait forever otherwise.
+// What comes after the first `MAX_OUTPUT` bytes is read too, and thrown away.
+fn read_pipe(mut pipe: impl Read + Send + 'static) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut text = Vec::new();
+        let _ = (&mut pipe).take(MAX_OUTPUT).read_to_end(&mut text);
+        let _ = io::copy(&mut pipe, &mut io::sink());
+        String::from_utf8_lossy(&text).into_owned()
+    })
+}
+
+/// Reads the diagnostics out of rustc's `--error-format=json` output, one JSON object per line.
+pub fn parse(stderr: &str) -> io::Result<Vec<Diagnostic>> {
+    let mut diagnostics = Vec::new();
+
+    for line in stderr.lines().filter(|line| line.starts_with('{')) {
+        let value = json::parse(line).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if let Some(diagnostic) = diagnostic(&value) {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    Ok(diagnostics)
+}
+
+fn diagnostic(value: &json::Value) -> Option<Diagnostic> {
+    let primary = value
+        .get("spans")?
+        .as_array()?
+        .iter()
+        .find(|span| span.get("is_primary").and_then(json::Value::as_bool) == Some(true));
+
+    Some(Diagnostic {
+        level: value.get("level")?.as_str()?.to_string(),
+        code: value.get("code").and_then(|code| code.get("code")).and_then(json::Value::as_str).map(String::from),
+        message: value.get("message")?.as_str()?.to_string(),
+        line: primary.and_then(|span| span.get("line_start")).and_then(json::Value::as_u64).map(|line| line as usize),
+        column: primary.and_then(|span| span.get("column_start")).and_then(json::Value::as_u64).map(|column| column as usize),
+        children: value
+            .get("children")
+            .and_then(json::Value::as_array)
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|child| Some(format!("{}: {}", child.get("level")?.as_str()?, child.get("message")?.as_str()?)))
+            .collect(),
+        rendered: value.get("rendered").and_then(json::Value::as_str).unwrap_or("").to_string(),
+    })
+}
@@ -0,0 +1,233 @@
+// Ownership Simulator ---
+// Runs a small subset of Rust without compiling it, and tells step by step what happens to each value:
+// which variable owns it, when it is moved, copied, cloned or borrowed, and when it is dropped.
+// It stops at the first error, with the same error code rustc would give and the rule of references it breaks.
+
+mod machine;
+mod parser;
+
+use std::fmt;
+
+use crate::diagram::Snapshot;
+use crate::lesson;
+
+/// Code the simulator can't parse or doesn't support.
+#[derive(Debug, PartialEq)]
+pub struct Error {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Error {
+    fn new(line: usize, message: impl Into<String>) -> Error {
+        Error { line, message: message.into() }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// One step of the simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    pub line: usize,
+    pub text: String,
+}
+
+/// The error that stopped the simulation, as rustc would report it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub line: usize,
+    /// The rustc error code, or an empty string for what the simulator itself doesn't support.
+    pub code: &'static str,
+    pub message: String,
+    pub notes: Vec<String>,
+    /// The rule of references the code breaks, for the borrow errors.
+    pub rule: Option<Rule>,
+}
+
+impl Diagnostic {
+    fn new(line: usize, code: &'static str, message: String) -> Diagnostic {
+        Diagnostic { line, code, message, notes: Vec::new(), rule: None }
+    }
+
+    fn unsupported(line: usize, message: String) -> Diagnostic {
+        Diagnostic::new(line, "", message)
+    }
+
+    fn note(mut self, note: String) -> Diagnostic {
+        self.notes.push(note);
+        self
+    }
+
+    fn rule(mut self, rule: Rule) -> Diagnostic {
+        self.rule = Some(rule);
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.code.is_empty() {
+            write!(f, "line {}: not supported: {}", self.line, self.message)?;
+        } else {
+            write!(f, "line {}: error[{}]: {}", self.line, self.code, self.message)?;
+        }
+        for note in &self.notes {
+            write!(f, "\n    {}", note)?;
+        }
+        if let Some(rule) = self.rule {
+            write!(f, "\n    this breaks the rule: {}", rule)?;
+        }
+        Ok(())
+    }
+}
+
+/// The Rules of References, as `references_and_borrowing` states them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rule {
+    OneMutableOrManyShared,
+    AlwaysValid,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Rule::OneMutableOrManyShared => "at any given time, you can have either one mutable reference or any number of immutable references",
+            Rule::AlwaysValid => "references must always be valid",
+        })
+    }
+}
+
+/// How long a borrow lasts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Liveness {
+    /// Until the last use of the reference, which is what rustc does.
+    NonLexical,
+    /// Until the reference goes out of scope, which is what rustc did before non-lexical lifetimes (Rust 2015).
+    Lexical,
+}
+
+/// Everything that happened while running a program.
+#[derive(Debug)]
+pub struct Trace {
+    pub events: Vec<Event>,
+    pub error: Option<Diagnostic>,
+    /// The stack and the heap of the function that runs first, or of the statements written outside of functions,
+    /// after each of its statements that changed them.
+    pub steps: Vec<Step>,
+}
+
+/// The stack and the heap after a statement. Their addresses are made up, and the `code` of the snapshot is the statement's line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    pub line: usize,
+    pub snapshot: Snapshot,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for event in &self.events {
+            writeln!(f, "line {}: {}", event.line, event.text)?;
+        }
+        match &self.error {
+            Some(error) => writeln!(f, "{}", error),
+            None => writeln!(f, "no error: every value follows the rules of ownership and borrowing"),
+        }
+    }
+}
+
+/// Runs `source`: the statements written outside of functions if there are any, otherwise `main`,
+/// otherwise the first function.
+pub fn simulate(source: &str) -> Result<Trace, Error> {
+    simulate_with(source, Liveness::NonLexical)
+}
+
+/// Runs `source` like `simulate`, with borrows lasting as long as `liveness` says.
+pub fn simulate_with(source: &str, liveness: Liveness) -> Result<Trace, Error> {
+    let program = parser::parse(source)?;
+    let mut trace = machine::run(&program, liveness);
+
+    let lines: Vec<&str> = source.lines().collect();
+    for step in &mut trace.steps {
+        step.snapshot.code = lines.get(step.line - 1).map_or_else(String::new, |line| code_of(line));
+    }
+    Ok(trace)
+}
+
+// A line without its indentation and its comment, if it has one.
+fn code_of(line: &str) -> String {
+    let mut in_string = false;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' if previous != '\\' => in_string = !in_string,
+            '/' if previous == '/' && !in_string => return line[..i - 1].trim().to_string(),
+            _ => {}
+        }
+        previous = c;
+    }
+    line.trim().to_string()
+}
+
+// Scenarios ---
+// The examples of the chapter modules, ready to simulate. Their source is taken from the modules themselves.
+
+pub struct Scenario {
+    pub name: &'static str,
+    /// The functions that make the scenario, `module::function`, starting with the one to run.
+    pub functions: &'static [&'static str],
+}
+
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario { name: "do_something", functions: &["ownership::do_something"] },
+    Scenario { name: "do_anything", functions: &["ownership::do_anything"] },
+    Scenario { name: "hello", functions: &["ownership::hello"] },
+    Scenario { name: "bye", functions: &["ownership::bye"] },
+    Scenario { name: "error", functions: &["ownership::error"] },
+    Scenario { name: "cloning", functions: &["ownership::cloning"] },
+    Scenario { name: "lets_go", functions: &["ownership::lets_go"] },
+    Scenario {
+        name: "main_function",
+        functions: &["ownership::main_function", "ownership::takes_ownership", "ownership::makes_copy"],
+    },
+    Scenario {
+        name: "another_main_function",
+        functions: &["ownership::another_main_function", "ownership::gives_ownership", "ownership::takes_and_gives_back"],
+    },
+    Scenario { name: "main_two", functions: &["ownership::main_two", "ownership::calculate_length"] },
+    Scenario {
+        name: "main_three",
+        functions: &["references_and_borrowing::main_three", "references_and_borrowing::calculate_length_two"],
+    },
+    Scenario { name: "main_four", functions: &["references_and_borrowing::main_four", "references_and_borrowing::change"] },
+    Scenario { name: "main_five", functions: &["references_and_borrowing::main_five", "references_and_borrowing::change_two"] },
+    Scenario { name: "fail", functions: &["references_and_borrowing::fail"] },
+    Scenario { name: "asd", functions: &["references_and_borrowing::asd"] },
+    Scenario { name: "dsa", functions: &["references_and_borrowing::dsa"] },
+    Scenario { name: "ddas", functions: &["references_and_borrowing::ddas"] },
+    // The example starts with main_six, which calls dangle.
+    Scenario { name: "dangle", functions: &["references_and_borrowing::dangle"] },
+    Scenario { name: "no_dangle", functions: &["references_and_borrowing::no_dangle"] },
+    Scenario { name: "main_same", functions: &["slice_type::main_same"] },
+];
+
+pub fn scenario(name: &str) -> Option<&'static Scenario> {
+    SCENARIOS.iter().find(|scenario| scenario.name == name)
+}
+
+impl Scenario {
+    /// The source of all the functions of the scenario, one after the other.
+    pub fn source(&self) -> String {
+        self.functions
+            .iter()
+            .map(|id| lesson::function_source(id).unwrap_or_else(|| panic!("the scenario {} needs `{}`", self.name, id)))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
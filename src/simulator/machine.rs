@@ -0,0 +1,1011 @@
+// Runs a parsed program while keeping track of who owns each value and who borrows it,
+// and narrates every move, copy, borrow and drop.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use super::parser::{Block, Expr, Function, Pattern, Program, Stmt};
+use super::{Diagnostic, Event, Liveness, Rule, Step, Trace};
+use crate::diagram::{Buffer, Frame as DiagramFrame, Slot, SlotValue, Snapshot};
+
+// Where the value of an expression statement goes.
+const TEMPORARY: &str = "a temporary";
+
+// Deep enough for any example, shallow enough to stop a recursive function quickly.
+const MAX_CALL_DEPTH: usize = 64;
+
+// Made up addresses for the diagrams: the simulated values don't live anywhere.
+const HEAP_START: usize = 0x5000_0000;
+const BUFFER_SPACING: usize = 0x40;
+const STATIC_START: usize = 0x1000_0000;
+
+#[derive(Clone, Debug)]
+enum Value {
+    Unit,
+    Int(i64),
+    /// A string literal, which is a `&str` and so is `Copy`.
+    Str(String),
+    /// A `String`, pointing at one of the heap allocations.
+    String(usize),
+    Tuple(Vec<Value>),
+    /// A reference, made by one of the loans.
+    Ref { loan: usize, mutable: bool },
+    /// A `&str` into the buffer of the String one of the loans borrows, `len` bytes from `start`.
+    Slice { loan: usize, start: usize, len: usize },
+}
+
+impl Value {
+    fn is_copy(&self) -> bool {
+        match self {
+            Value::Unit | Value::Int(_) | Value::Str(_) => true,
+            Value::String(_) => false,
+            Value::Tuple(items) => items.iter().all(Value::is_copy),
+            Value::Ref { mutable, .. } => !mutable,
+            Value::Slice { .. } => true,
+        }
+    }
+
+    fn loans(&self) -> Vec<usize> {
+        match self {
+            Value::Ref { loan, .. } | Value::Slice { loan, .. } => vec![*loan],
+            Value::Tuple(items) => items.iter().flat_map(Value::loans).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    /// Declared with `let x;` and not assigned yet.
+    Uninit,
+    Live(Value),
+    Moved { into: String, line: usize },
+}
+
+/// When a binding was looked up. Ticks count every lookup, so they order uses even on the same line.
+#[derive(Clone, Copy, Debug)]
+struct Use {
+    tick: usize,
+    line: usize,
+}
+
+#[derive(Debug)]
+struct Binding {
+    name: String,
+    mutable: bool,
+    state: State,
+    in_scope: bool,
+    last_use: Option<Use>,
+}
+
+/// A heap allocation made by a `String`.
+#[derive(Debug)]
+struct Allocation {
+    text: String,
+    /// The largest the text has been: clearing a `String` keeps its buffer.
+    capacity: usize,
+    freed: bool,
+}
+
+/// A `&` or `&mut` borrow of a binding.
+#[derive(Debug)]
+struct Loan {
+    target: usize,
+    mutable: bool,
+    line: usize,
+    /// The bindings that hold the reference, or a copy of it.
+    holders: Vec<usize>,
+    /// Whether the end of the loan was already told.
+    ended: bool,
+}
+
+struct Frame {
+    function: String,
+    /// Indexes into `Machine::bindings`, innermost scope last, in declaration order.
+    scopes: Vec<Vec<usize>>,
+}
+
+struct Machine<'p> {
+    functions: HashMap<&'p str, &'p Function>,
+    bindings: Vec<Binding>,
+    frames: Vec<Frame>,
+    heap: Vec<Allocation>,
+    loans: Vec<Loan>,
+    events: Vec<Event>,
+    tick: usize,
+    liveness: Liveness,
+    /// The last use of every binding, known from a first run of the same program.
+    /// The first run has none and doesn't check borrows: it is only there to find out when each binding is last used.
+    future: Option<Vec<Option<Use>>>,
+    /// The stack and the heap after each statement of the outermost function that changed them.
+    steps: Vec<Step>,
+}
+
+type Result<T> = std::result::Result<T, Diagnostic>;
+
+pub fn run(program: &Program, liveness: Liveness) -> Trace {
+    if let Some(error) = check_signatures(program) {
+        return Trace { events: Vec::new(), error: Some(error), steps: Vec::new() };
+    }
+
+    let mut first = Machine::new(program, liveness, None);
+    // Errors are found again by the second run, which stops at the same place or before.
+    let _ = first.run_program(program);
+    let future = first.bindings.iter().map(|binding| binding.last_use).collect();
+
+    let mut machine = Machine::new(program, liveness, Some(future));
+    let result = machine.run_program(program);
+    Trace { events: machine.events, error: result.err(), steps: machine.steps }
+}
+
+// A function returning a reference must say what it borrows from, unless it has exactly one reference parameter.
+fn check_signatures(program: &Program) -> Option<Diagnostic> {
+    program.functions.iter().find_map(|function| {
+        let returns = function.returns.as_ref()?;
+        let borrowed_params = function.params.iter().filter(|param| param.ty.has_reference()).count();
+        if !returns.has_elided_lifetime() || borrowed_params == 1 {
+            return None;
+        }
+
+        let note = if borrowed_params == 0 {
+            "this function's return type contains a borrowed value, but there is no value for it to be borrowed from"
+        } else {
+            "this function's return type contains a borrowed value, but the signature does not say which parameter it is borrowed from"
+        };
+        Some(
+            Diagnostic::new(function.line, "E0106", "missing lifetime specifier".to_string())
+                .note(note.to_string())
+                .rule(Rule::AlwaysValid),
+        )
+    })
+}
+
+impl<'p> Machine<'p> {
+    fn new(program: &'p Program, liveness: Liveness, future: Option<Vec<Option<Use>>>) -> Machine<'p> {
+        Machine {
+            functions: program.functions.iter().map(|function| (function.name.as_str(), function)).collect(),
+            bindings: Vec::new(),
+            frames: Vec::new(),
+            heap: Vec::new(),
+            loans: Vec::new(),
+            events: Vec::new(),
+            tick: 0,
+            liveness,
+            future,
+            steps: Vec::new(),
+        }
+    }
+
+    // Runs the statements written outside of functions if there are any, otherwise `main` or the first function.
+    fn run_program(&mut self, program: &'p Program) -> Result<()> {
+        if !program.statements.is_empty() {
+            self.frames.push(Frame { function: "main".to_string(), scopes: vec![Vec::new()] });
+            for stmt in &program.statements {
+                self.statement(stmt)?;
+            }
+            return self.exit_scope(program.end_line);
+        }
+
+        let entry = program
+            .functions
+            .iter()
+            .find(|function| function.name == "main")
+            .or_else(|| program.functions.first());
+
+        match entry {
+            Some(function) if function.params.is_empty() => {
+                let value = self.call(function, Vec::new(), function.line)?;
+                self.drop_temporary(value, function.body.end_line);
+                Ok(())
+            }
+            Some(function) => Err(Diagnostic::unsupported(function.line, format!("can't start at `{}`, it takes parameters", function.name))),
+            None => Ok(()),
+        }
+    }
+
+    fn event(&mut self, line: usize, text: String) {
+        self.events.push(Event { line, text });
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("there is always a frame while running")
+    }
+
+    fn declare(&mut self, name: &str, mutable: bool, state: State) -> usize {
+        let loans = match &state {
+            State::Live(value) => value.loans(),
+            _ => Vec::new(),
+        };
+        self.bindings.push(Binding { name: name.to_string(), mutable, state, in_scope: true, last_use: None });
+        let index = self.bindings.len() - 1;
+        self.frame().scopes.last_mut().expect("there is always a scope").push(index);
+        self.hold(index, loans);
+        index
+    }
+
+    // Records that the binding `index` now holds a reference made by each of `loans`.
+    fn hold(&mut self, index: usize, loans: Vec<usize>) {
+        for loan in loans {
+            self.loans[loan].holders.push(index);
+        }
+    }
+
+    // Finds a variable and records that it is used now.
+    fn lookup(&mut self, name: &str, line: usize) -> Result<usize> {
+        let frame = self.frames.last().expect("there is always a frame while running");
+        let index = frame
+            .scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .copied()
+            .find(|&index| self.bindings[index].name == name)
+            .ok_or_else(|| Diagnostic::new(line, "E0425", format!("cannot find value `{}` in this scope", name)))?;
+
+        self.tick += 1;
+        self.bindings[index].last_use = Some(Use { tick: self.tick, line });
+        Ok(index)
+    }
+
+    fn statement(&mut self, stmt: &'p Stmt) -> Result<()> {
+        self.end_unused_loans();
+        self.run_statement(stmt)?;
+
+        // Only the outermost function is drawn, after each statement that changes what it holds.
+        if self.frames.len() == 1 && self.checking() {
+            let line = match stmt {
+                Stmt::Expr(Expr::Block(block)) => Some(block.end_line),
+                stmt => stmt.line(),
+            };
+            let snapshot = self.snapshot();
+            if let Some(line) = line.filter(|_| self.steps.last().is_none_or(|step| step.snapshot != snapshot)) {
+                self.steps.push(Step { line, snapshot });
+            }
+        }
+        Ok(())
+    }
+
+    fn run_statement(&mut self, stmt: &'p Stmt) -> Result<()> {
+        match stmt {
+            Stmt::Let { pattern, value: None, line } => {
+                self.bind_uninit(pattern, *line);
+                Ok(())
+            }
+            Stmt::Let { pattern, value: Some(value), line } => {
+                let into = pattern_name(pattern);
+                let value = self.eval(value, &into)?;
+                self.bind(pattern, value, *line)
+            }
+            Stmt::Assign { name, value, line } => self.assign(name, value, *line),
+            Stmt::Expr(expr) => {
+                let value = self.eval(expr, TEMPORARY)?;
+                let line = expr.line().unwrap_or(0);
+                self.drop_temporary(value, line);
+                Ok(())
+            }
+        }
+    }
+
+    fn bind_uninit(&mut self, pattern: &Pattern, line: usize) {
+        match pattern {
+            Pattern::Name { name, mutable } => {
+                self.declare(name, *mutable, State::Uninit);
+                self.event(line, format!("{} is declared but not initialized yet", name));
+            }
+            Pattern::Tuple(items) => items.iter().for_each(|item| self.bind_uninit(item, line)),
+        }
+    }
+
+    fn bind(&mut self, pattern: &Pattern, value: Value, line: usize) -> Result<()> {
+        match (pattern, value) {
+            (Pattern::Name { name, mutable }, value) => {
+                self.declare(name, *mutable, State::Live(value));
+                Ok(())
+            }
+            (Pattern::Tuple(patterns), Value::Tuple(values)) if patterns.len() == values.len() => {
+                for (pattern, value) in patterns.iter().zip(values) {
+                    self.bind(pattern, value, line)?;
+                }
+                Ok(())
+            }
+            (_, value) => {
+                let message = format!("mismatched types: can't destructure a {}", self.type_name(&value));
+                Err(Diagnostic::new(line, "E0308", message))
+            }
+        }
+    }
+
+    fn assign(&mut self, name: &str, value: &'p Expr, line: usize) -> Result<()> {
+        let index = self.lookup(name, line)?;
+        // The new value comes first, as in rustc, but if the assignment itself is an error it never happens:
+        // what evaluating it told, like a new String being allocated, is taken back.
+        let told = self.events.len();
+        let value = self.eval(value, name)?;
+        let binding = &self.bindings[index];
+
+        if !binding.mutable && !matches!(binding.state, State::Uninit) {
+            self.events.truncate(told);
+            return Err(Diagnostic::new(line, "E0384", format!("cannot assign twice to immutable variable `{}`", name))
+                .note(format!("help: consider making this binding mutable: `mut {}`", name)));
+        }
+        if let Some(loan) = self.live_loan_of(index) {
+            self.events.truncate(told);
+            let message = format!("cannot assign to `{}` because it is borrowed", name);
+            return Err(self.conflict("E0506", message, loan, line, Rule::AlwaysValid));
+        }
+
+        self.hold(index, value.loans());
+        let old = std::mem::replace(&mut self.bindings[index].state, State::Live(value));
+        match old {
+            State::Live(old) => {
+                self.event(line, format!("{} gets a new value, so its old value is dropped first", name));
+                self.drop_value(old, name, line);
+            }
+            State::Moved { .. } => self.event(line, format!("{} was moved out of, it becomes valid again with its new value", name)),
+            State::Uninit => self.event(line, format!("{} is initialized", name)),
+        }
+        Ok(())
+    }
+
+    // Evaluates an expression whose value goes somewhere: a variable is moved (or copied) into `into`.
+    fn eval(&mut self, expr: &'p Expr, into: &str) -> Result<Value> {
+        match expr {
+            Expr::Var { name, line } => self.take(name, into, *line),
+            Expr::Tuple(items) => {
+                let mut values = Vec::new();
+                for item in items {
+                    values.push(self.eval(item, into)?);
+                }
+                Ok(Value::Tuple(values))
+            }
+            _ => self.compute(expr, into),
+        }
+    }
+
+    // Evaluates an expression that only looks at variables: they are borrowed, not moved.
+    fn read(&mut self, expr: &'p Expr) -> Result<Value> {
+        match expr {
+            Expr::Var { name, line } => {
+                let index = self.lookup(name, *line)?;
+                let value = self.readable(index, *line)?;
+                self.check_borrow(index, false, *line)?;
+                Ok(value)
+            }
+            Expr::Tuple(items) => {
+                let mut values = Vec::new();
+                for item in items {
+                    values.push(self.read(item)?);
+                }
+                Ok(Value::Tuple(values))
+            }
+            _ => self.compute(expr, TEMPORARY),
+        }
+    }
+
+    // The expressions that produce a new value, whatever the context.
+    fn compute(&mut self, expr: &'p Expr, into: &str) -> Result<Value> {
+        match expr {
+            Expr::Int(value) => Ok(Value::Int(*value)),
+            Expr::Str(text) => Ok(Value::Str(text.clone())),
+            Expr::NewString { text, line } => {
+                let id = self.allocate(text.clone());
+                self.event(*line, format!("{} owns a new String {:?}, stored in heap buffer #{}", into, text, id));
+                Ok(Value::String(id))
+            }
+            Expr::Block(block) => {
+                self.frame().scopes.push(Vec::new());
+                let value = self.block(block, into)?;
+                self.exit_scope(block.end_line)?;
+                Ok(value)
+            }
+            Expr::Call { name, args, line } => {
+                let value = self.call_by_name(name, args, *line)?;
+                if !value.is_copy() && into != TEMPORARY {
+                    self.event(*line, format!("{} takes ownership of the value returned by {}", into, name));
+                }
+                Ok(value)
+            }
+            Expr::Method { receiver, method, args, line } => self.method(receiver, method, args, *line),
+            Expr::Ref { mutable, operand, line } => self.borrow(operand, *mutable, into, *line),
+            Expr::Slice { line, .. } => Err(Diagnostic::new(
+                *line,
+                "E0277",
+                "the size for values of type `str` cannot be known at compilation time".to_string(),
+            )
+            .note("help: borrow the slice instead, with `&`".to_string())),
+            Expr::Print { newline, format, args, line } => {
+                let mut values = Vec::new();
+                for arg in args {
+                    values.push(self.read(arg)?);
+                }
+                let text = self.format(format, &values, *line)?;
+                let macro_name = if *newline { "println!" } else { "print!" };
+                self.event(*line, format!("{} prints {:?}", macro_name, text));
+                Ok(Value::Unit)
+            }
+            Expr::Var { .. } | Expr::Tuple(_) => self.eval(expr, into),
+        }
+    }
+
+    // Runs the statements of a block in the current scope and evaluates its tail into `into`.
+    fn block(&mut self, block: &'p Block, into: &str) -> Result<Value> {
+        for stmt in &block.statements {
+            self.statement(stmt)?;
+        }
+        self.end_unused_loans();
+        match &block.tail {
+            Some(tail) => self.eval(tail, into),
+            None => Ok(Value::Unit),
+        }
+    }
+
+    fn exit_scope(&mut self, line: usize) -> Result<()> {
+        self.end_unused_loans();
+        let scope = self.frame().scopes.pop().unwrap_or_default();
+
+        for index in scope.into_iter().rev() {
+            if matches!(self.bindings[index].state, State::Live(_)) {
+                if let Some(loan) = self.live_loan_of(index) {
+                    let name = self.bindings[index].name.clone();
+                    let message = format!("`{}` does not live long enough", name);
+                    return Err(self
+                        .conflict("E0597", message, loan, line, Rule::AlwaysValid)
+                        .note(format!("`{}` dropped here while still borrowed", name)));
+                }
+            }
+
+            self.bindings[index].in_scope = false;
+            let state = std::mem::replace(&mut self.bindings[index].state, State::Uninit);
+            let name = self.bindings[index].name.clone();
+            match state {
+                State::Live(value) => {
+                    self.event(line, format!("{} goes out of scope", name));
+                    self.drop_value(value, &name, line);
+                }
+                State::Moved { into, line: moved } => self.event(
+                    line,
+                    format!("{} goes out of scope, but it was moved into {} at line {}, so nothing happens", name, into, moved),
+                ),
+                State::Uninit => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn drop_value(&mut self, value: Value, owner: &str, line: usize) {
+        match value {
+            Value::String(id) => {
+                self.heap[id - 1].freed = true;
+                self.event(line, format!("drop({}) frees heap buffer #{}", owner, id));
+            }
+            Value::Tuple(items) => items.into_iter().for_each(|item| self.drop_value(item, owner, line)),
+            Value::Ref { loan, .. } | Value::Slice { loan, .. } => self.end_loan_out_of_scope(loan, owner, line),
+            Value::Unit | Value::Int(_) | Value::Str(_) => {}
+        }
+    }
+
+    // A value that nobody owns, like the result of a call used as a statement, is dropped right away.
+    fn drop_temporary(&mut self, value: Value, line: usize) {
+        if !value.is_copy() {
+            self.event(line, "the value nobody took ownership of is dropped at the end of the statement".to_string());
+            self.drop_value(value, "the temporary", line);
+        }
+    }
+
+    fn allocate(&mut self, text: String) -> usize {
+        self.heap.push(Allocation { capacity: text.len(), text, freed: false });
+        self.heap.len()
+    }
+
+    // Moves or copies the value of a variable into `into`.
+    fn take(&mut self, name: &str, into: &str, line: usize) -> Result<Value> {
+        let index = self.lookup(name, line)?;
+        let value = match &self.bindings[index].state {
+            State::Live(value) => value.clone(),
+            State::Moved { into: moved_into, line: moved } => {
+                return Err(Diagnostic::new(line, "E0382", format!("use of moved value: `{}`", name))
+                    .note(format!("value moved into {} at line {}", moved_into, moved))
+                    .note(format!("move occurs because `{}` has type `String`, which does not implement the `Copy` trait", name)))
+            }
+            State::Uninit => return Err(uninitialized(name, line)),
+        };
+
+        if value.is_copy() {
+            self.check_borrow(index, false, line)?;
+            let type_name = self.type_name(&value);
+            self.event(line, format!("{} is copied into {} ({} is Copy), {} is still valid", name, into, type_name, name));
+        } else {
+            if let Some(loan) = self.live_loan_of(index) {
+                let message = format!("cannot move out of `{}` because it is borrowed", name);
+                return Err(self.conflict("E0505", message, loan, line, Rule::AlwaysValid));
+            }
+            self.bindings[index].state = State::Moved { into: into.to_string(), line };
+            self.event(line, format!("{} moved into {}; {} invalid", name, into, name));
+        }
+        Ok(value)
+    }
+
+    // The value of a variable that is about to be borrowed.
+    fn readable(&self, index: usize, line: usize) -> Result<Value> {
+        let binding = &self.bindings[index];
+        match &binding.state {
+            State::Live(value) => Ok(value.clone()),
+            State::Moved { into, line: moved } => Err(Diagnostic::new(line, "E0382", format!("borrow of moved value: `{}`", binding.name))
+                .note(format!("value moved into {} at line {}", into, moved))
+                .note("value borrowed here after move".to_string())),
+            State::Uninit => Err(uninitialized(&binding.name, line)),
+        }
+    }
+
+    // `&operand` or `&mut operand`, given to `into`.
+    fn borrow(&mut self, operand: &'p Expr, mutable: bool, into: &str, line: usize) -> Result<Value> {
+        let name = match operand {
+            Expr::Var { name, .. } => name,
+            Expr::Slice { base, start, end, .. } if !mutable => match &**base {
+                Expr::Var { name, .. } => return self.slice(name, start.as_deref(), end.as_deref(), into, line),
+                _ => return Err(Diagnostic::unsupported(line, "only variables can be sliced".to_string())),
+            },
+            _ => return Err(Diagnostic::unsupported(line, "only variables can be borrowed".to_string())),
+        };
+        let index = self.lookup(name, line)?;
+        self.readable(index, line)?;
+
+        if mutable && !self.bindings[index].mutable {
+            return Err(not_mutable(name, line));
+        }
+        self.check_borrow(index, mutable, line)?;
+
+        self.loans.push(Loan { target: index, mutable, line, holders: Vec::new(), ended: false });
+        let kind = if mutable { "mutably with &mut" } else { "with &" };
+        self.event(line, format!("{} borrows {} {}", into, name, kind));
+        Ok(Value::Ref { loan: self.loans.len() - 1, mutable })
+    }
+
+    // `&name[start..end]`: a slice of the String `name` owns or refers to, which borrows that String,
+    // or a shorter string literal.
+    fn slice(&mut self, name: &str, start: Option<&'p Expr>, end: Option<&'p Expr>, into: &str, line: usize) -> Result<Value> {
+        let index = self.lookup(name, line)?;
+        let value = self.readable(index, line)?;
+        if let Value::Slice { .. } = value {
+            return Err(Diagnostic::unsupported(line, "slices of slices are not supported".to_string()));
+        }
+        let text = match self.deref(&value) {
+            Value::String(id) => self.heap[id - 1].text.clone(),
+            Value::Str(text) => text,
+            other => return Err(Diagnostic::new(line, "E0608", format!("cannot index into a value of type `{}`", self.type_name(&other)))),
+        };
+
+        let start = self.bound(start, 0, line)?;
+        let end = self.bound(end, text.len(), line)?;
+        if start > end || end > text.len() || !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+            let message = format!("`&{}[{}..{}]` panics: {:?} is {} bytes long", name, start, end, text, text.len());
+            return Err(Diagnostic::unsupported(line, message));
+        }
+
+        // The loan is of the String itself, even when the slice is taken through a reference to it.
+        let mut target = index;
+        while let State::Live(Value::Ref { loan, .. }) = &self.bindings[target].state {
+            target = self.loans[*loan].target;
+        }
+        let id = match &self.bindings[target].state {
+            State::Live(Value::String(id)) => *id,
+            _ => return Ok(Value::Str(text[start..end].to_string())),
+        };
+        self.check_borrow(target, false, line)?;
+
+        self.loans.push(Loan { target, mutable: false, line, holders: Vec::new(), ended: false });
+        let len = end - start;
+        self.event(line, format!("{} borrows {}[{}..{}] with &, {} bytes of heap buffer #{} from byte {}", into, name, start, end, len, id, start));
+        Ok(Value::Slice { loan: self.loans.len() - 1, start, len })
+    }
+
+    // A bound of a range, `default` if it is left out.
+    fn bound(&mut self, bound: Option<&'p Expr>, default: usize, line: usize) -> Result<usize> {
+        let value = match bound {
+            Some(bound) => self.read(bound)?,
+            None => return Ok(default),
+        };
+        match value {
+            Value::Int(bound) => usize::try_from(bound).map_err(|_| Diagnostic::unsupported(line, format!("`{}` is not a valid index", bound))),
+            other => Err(Diagnostic::new(line, "E0308", format!("mismatched types: expected `usize`, found `{}`", self.type_name(&other)))),
+        }
+    }
+
+    fn checking(&self) -> bool {
+        self.future.is_some()
+    }
+
+    // The last time one of the holders of a loan is used from now on, if it is used again.
+    fn later_use(&self, loan: &Loan) -> Option<Use> {
+        let future = self.future.as_ref()?;
+        loan.holders
+            .iter()
+            .filter_map(|&holder| future.get(holder).copied().flatten())
+            .filter(|used| used.tick > self.tick)
+            .max_by_key(|used| used.tick)
+    }
+
+    fn is_live(&self, loan: &Loan) -> bool {
+        if !self.checking() {
+            return false;
+        }
+        match self.liveness {
+            Liveness::NonLexical => self.later_use(loan).is_some(),
+            Liveness::Lexical => loan.holders.iter().any(|&holder| self.bindings[holder].in_scope),
+        }
+    }
+
+    // A loan of the binding `index` that is still live.
+    fn live_loan_of(&self, index: usize) -> Option<usize> {
+        (0..self.loans.len()).find(|&loan| self.loans[loan].target == index && self.is_live(&self.loans[loan]))
+    }
+
+    // Checks that the binding `index` can be borrowed now, mutably or not, against the loans that are still live.
+    fn check_borrow(&self, index: usize, mutable: bool, line: usize) -> Result<()> {
+        let name = &self.bindings[index].name;
+        for loan in (0..self.loans.len()).filter(|&loan| self.loans[loan].target == index && self.is_live(&self.loans[loan])) {
+            let (code, message) = match (mutable, self.loans[loan].mutable) {
+                (true, true) => ("E0499", format!("cannot borrow `{}` as mutable more than once at a time", name)),
+                (true, false) => ("E0502", format!("cannot borrow `{}` as mutable because it is also borrowed as immutable", name)),
+                (false, true) => ("E0502", format!("cannot borrow `{}` as immutable because it is also borrowed as mutable", name)),
+                (false, false) => continue,
+            };
+            return Err(self.conflict(code, message, loan, line, Rule::OneMutableOrManyShared));
+        }
+        Ok(())
+    }
+
+    // The error for doing something to a binding while `loan` of it is live.
+    fn conflict(&self, code: &'static str, message: String, loan: usize, line: usize, rule: Rule) -> Diagnostic {
+        let loan = &self.loans[loan];
+        let kind = if loan.mutable { "mutable" } else { "immutable" };
+        let holder = loan.holders.first().map_or(TEMPORARY, |&holder| self.bindings[holder].name.as_str());
+
+        let why = match (self.liveness, self.later_use(loan)) {
+            (Liveness::NonLexical, Some(used)) => format!("the {} borrow is later used at line {}", kind, used.line),
+            _ => format!("{} is still in scope, and with lexical lifetimes a borrow lasts until the end of the scope", holder),
+        };
+
+        Diagnostic::new(line, code, message)
+            .note(format!("{} borrow by {} occurs at line {}", kind, holder, loan.line))
+            .note(why)
+            .rule(rule)
+    }
+
+    // With non-lexical lifetimes, a loan ends as soon as its holders are not used anymore: tell when that happened.
+    fn end_unused_loans(&mut self) {
+        if self.liveness != Liveness::NonLexical || !self.checking() {
+            return;
+        }
+
+        for loan in 0..self.loans.len() {
+            let ended = {
+                let loan = &self.loans[loan];
+                !loan.ended && !loan.holders.is_empty() && !self.is_live(loan)
+            };
+            if !ended {
+                continue;
+            }
+
+            self.loans[loan].ended = true;
+            let holder = self.loans[loan].holders[0];
+            let name = self.bindings[holder].name.clone();
+            let target = self.bindings[self.loans[loan].target].name.clone();
+            let last = self.future.as_ref().and_then(|future| future[holder]).map_or(self.loans[loan].line, |used| used.line);
+            self.event(last, format!("{} is not used after this line, so its borrow of {} ends here", name, target));
+        }
+    }
+
+    // With lexical lifetimes, a loan ends when the last reference it made goes out of scope.
+    fn end_loan_out_of_scope(&mut self, loan: usize, owner: &str, line: usize) {
+        if self.liveness != Liveness::Lexical || self.loans[loan].ended || self.is_live(&self.loans[loan]) {
+            return;
+        }
+        self.loans[loan].ended = true;
+        let target = self.bindings[self.loans[loan].target].name.clone();
+        self.event(line, format!("the borrow of {} by {} ends with it", target, owner));
+    }
+
+    fn call_by_name(&mut self, name: &str, args: &'p [Expr], line: usize) -> Result<Value> {
+        if name == "drop" && !self.functions.contains_key("drop") {
+            let [arg] = args else {
+                return Err(Diagnostic::new(line, "E0061", "`drop` takes 1 argument".to_string()));
+            };
+            let value = self.eval(arg, "drop")?;
+            self.drop_value(value, "the argument", line);
+            return Ok(Value::Unit);
+        }
+
+        let function = *self
+            .functions
+            .get(name)
+            .ok_or_else(|| Diagnostic::new(line, "E0425", format!("cannot find function `{}` in this scope", name)))?;
+        if function.params.len() != args.len() {
+            return Err(Diagnostic::new(
+                line,
+                "E0061",
+                format!("`{}` takes {} arguments but {} were supplied", name, function.params.len(), args.len()),
+            ));
+        }
+
+        let mut values = Vec::new();
+        for (param, arg) in function.params.iter().zip(args) {
+            let into = format!("{}'s parameter {}", name, param.name);
+            values.push(self.argument(arg, &into)?);
+        }
+        self.call(function, values, line)
+    }
+
+    // Passing a `&mut` variable to a function reborrows it instead of moving it.
+    fn argument(&mut self, arg: &'p Expr, into: &str) -> Result<Value> {
+        if let Expr::Var { name, line } = arg {
+            let index = self.lookup(name, *line)?;
+            if let State::Live(value @ Value::Ref { mutable: true, .. }) = &self.bindings[index].state {
+                let value = value.clone();
+                self.event(*line, format!("{} reborrows {} for the call", into, name));
+                return Ok(value);
+            }
+        }
+        self.eval(arg, into)
+    }
+
+    fn call(&mut self, function: &'p Function, args: Vec<Value>, line: usize) -> Result<Value> {
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            return Err(Diagnostic::unsupported(line, format!("`{}` calls itself too deeply", function.name)));
+        }
+
+        self.event(line, format!("call {}", function.name));
+        self.frames.push(Frame { function: function.name.clone(), scopes: vec![Vec::new()] });
+        for (param, value) in function.params.iter().zip(args) {
+            self.declare(&param.name, param.mutable, State::Live(value));
+        }
+
+        let into = format!("the caller of {}", function.name);
+        let value = self.block(&function.body, &into)?;
+
+        // A reference to one of the function's own variables would outlive it.
+        let locals: Vec<usize> = self.frame().scopes.iter().flatten().copied().collect();
+        if let Some(target) = value.loans().into_iter().map(|loan| self.loans[loan].target).find(|target| locals.contains(target)) {
+            let name = &self.bindings[target].name;
+            return Err(Diagnostic::new(function.body.end_line, "E0515", format!("cannot return reference to local variable `{}`", name))
+                .note(format!("`{}` is dropped when {} returns", name, function.name))
+                .rule(Rule::AlwaysValid));
+        }
+
+        self.exit_scope(function.body.end_line)?;
+        self.frames.pop();
+
+        if !matches!(value, Value::Unit) {
+            self.event(function.body.end_line, format!("{} returns its value to the caller", function.name));
+        }
+        Ok(value)
+    }
+
+    fn method(&mut self, receiver: &'p Expr, method: &str, args: &'p [Expr], line: usize) -> Result<Value> {
+        let target = match receiver {
+            Expr::Var { name, .. } => Some(name.as_str()),
+            _ => None,
+        };
+        let described = target.map_or_else(|| "the value".to_string(), String::from);
+
+        match (method, args) {
+            ("clone", []) => {
+                let value = self.read(receiver)?;
+                let text = self.string_of(&value, method, line)?;
+                let id = self.allocate(text);
+                self.event(line, format!("{} is cloned: its heap data is deep copied into heap buffer #{}", described, id));
+                Ok(Value::String(id))
+            }
+            ("len", []) => {
+                let value = self.read(receiver)?;
+                let text = self.string_of(&value, method, line)?;
+                Ok(Value::Int(text.len() as i64))
+            }
+            ("push_str", [_]) | ("clear", []) => {
+                let arg = arg_value(self, args)?;
+                let id = self.mutable_string(receiver, line)?;
+                match arg {
+                    Some(Value::Str(text)) => {
+                        let allocation = &mut self.heap[id - 1];
+                        allocation.text.push_str(&text);
+                        allocation.capacity = allocation.capacity.max(allocation.text.len());
+                    }
+                    Some(_) => return Err(Diagnostic::new(line, "E0308", "`push_str` takes a string literal".to_string())),
+                    None => self.heap[id - 1].text.clear(),
+                }
+                let text = self.heap[id - 1].text.clone();
+                self.event(line, format!("{} is borrowed mutably by {}, heap buffer #{} now holds {:?}", described, method, id, text));
+                Ok(Value::Unit)
+            }
+            _ => Err(Diagnostic::unsupported(line, format!("the method `{}` is not supported", method))),
+        }
+    }
+
+    // The heap buffer behind a receiver that a method is about to modify, directly or through a `&mut`.
+    fn mutable_string(&mut self, receiver: &'p Expr, line: usize) -> Result<usize> {
+        let name = match receiver {
+            Expr::Var { name, .. } => name,
+            _ => return Err(Diagnostic::unsupported(line, "only variables can be modified".to_string())),
+        };
+        let index = self.lookup(name, line)?;
+
+        let value = match self.readable(index, line)? {
+            Value::Ref { mutable: false, .. } => {
+                return Err(Diagnostic::new(line, "E0596", format!("cannot borrow `*{}` as mutable, as it is behind a `&` reference", name))
+                    .note(format!("`{}` is a `&` reference, so the data it refers to cannot be borrowed as mutable", name)))
+            }
+            Value::Ref { loan, mutable: true } => self.readable(self.loans[loan].target, line)?,
+            value => {
+                if !self.bindings[index].mutable {
+                    return Err(not_mutable(name, line));
+                }
+                self.check_borrow(index, true, line)?;
+                value
+            }
+        };
+
+        match value {
+            Value::String(id) => Ok(id),
+            other => Err(Diagnostic::new(line, "E0599", format!("no such method on `{}`", self.type_name(&other)))),
+        }
+    }
+
+    // Follows references to the value they point at.
+    fn deref(&self, value: &Value) -> Value {
+        match value {
+            Value::Ref { loan, .. } => match &self.bindings[self.loans[*loan].target].state {
+                State::Live(target) => self.deref(target),
+                _ => Value::Unit,
+            },
+            Value::Slice { loan, start, len } => match self.deref(&Value::Ref { loan: *loan, mutable: false }) {
+                Value::String(id) => Value::Str(self.heap[id - 1].text.get(*start..start + len).unwrap_or_default().to_string()),
+                _ => Value::Unit,
+            },
+            value => value.clone(),
+        }
+    }
+
+    fn string_of(&self, value: &Value, method: &str, line: usize) -> Result<String> {
+        match self.deref(value) {
+            Value::String(id) => Ok(self.heap[id - 1].text.clone()),
+            Value::Str(text) if method == "len" => Ok(text),
+            other => Err(Diagnostic::new(line, "E0599", format!("no method named `{}` found for `{}` here", method, self.type_name(&other)))),
+        }
+    }
+
+    fn type_name(&self, value: &Value) -> String {
+        match value {
+            Value::Unit => "()".to_string(),
+            Value::Int(_) => "i32".to_string(),
+            Value::Str(_) | Value::Slice { .. } => "&str".to_string(),
+            Value::String(_) => "String".to_string(),
+            Value::Tuple(items) => format!("({})", items.iter().map(|item| self.type_name(item)).collect::<Vec<_>>().join(", ")),
+            Value::Ref { loan, mutable } => {
+                let target = match &self.bindings[self.loans[*loan].target].state {
+                    State::Live(target) => self.type_name(target),
+                    _ => "_".to_string(),
+                };
+                format!("&{}{}", if *mutable { "mut " } else { "" }, target)
+            }
+        }
+    }
+
+    fn display(&self, value: &Value) -> String {
+        match self.deref(value) {
+            Value::Unit => "()".to_string(),
+            Value::Int(value) => value.to_string(),
+            Value::Str(text) => text,
+            Value::String(id) => self.heap[id - 1].text.clone(),
+            Value::Tuple(items) => format!("({})", items.iter().map(|item| self.display(item)).collect::<Vec<_>>().join(", ")),
+            Value::Ref { .. } | Value::Slice { .. } => unreachable!("deref follows every reference"),
+        }
+    }
+
+    // The variables of the current frame and the buffers they own, drawn the way `diagram` draws real values.
+    fn snapshot(&self) -> Snapshot {
+        let mut snapshot = Snapshot::new("");
+        let mut slots = Vec::new();
+        let scopes = self.frames.last().map_or(&[][..], |frame| &frame.scopes[..]);
+
+        for &index in scopes.iter().flatten() {
+            let binding = &self.bindings[index];
+            let value = match &binding.state {
+                State::Live(value) => self.slot_value(value, &mut snapshot.heap),
+                State::Moved { into, .. } => SlotValue::Moved { into: into.clone() },
+                State::Uninit => continue,
+            };
+            slots.push(Slot { name: binding.name.clone(), value });
+        }
+
+        let function = self.frames.last().map_or_else(String::new, |frame| frame.function.clone());
+        snapshot.frames.push(DiagramFrame { function, slots });
+        snapshot
+    }
+
+    fn slot_value(&self, value: &Value, heap: &mut Vec<Buffer>) -> SlotValue {
+        match value {
+            Value::String(id) => {
+                let allocation = &self.heap[id - 1];
+                let ptr = self.buffer(*id, heap);
+                SlotValue::String { ptr, len: allocation.text.len(), capacity: allocation.capacity }
+            }
+            Value::Slice { loan, start, len } => match self.deref(&Value::Ref { loan: *loan, mutable: false }) {
+                Value::String(id) => SlotValue::Str { ptr: self.buffer(id, heap) + start, len: *len },
+                _ => SlotValue::Str { ptr: STATIC_START, len: *len },
+            },
+            Value::Str(text) => SlotValue::Str { ptr: STATIC_START, len: text.len() },
+            Value::Ref { loan, mutable } => {
+                SlotValue::Ref { to: self.bindings[self.loans[*loan].target].name.clone(), mutable: *mutable }
+            }
+            value => SlotValue::Plain(self.display(value)),
+        }
+    }
+
+    // The address of heap buffer `id`, which is drawn if it was ever allocated.
+    fn buffer(&self, id: usize, heap: &mut Vec<Buffer>) -> usize {
+        let allocation = &self.heap[id - 1];
+        let ptr = HEAP_START + (id - 1) * BUFFER_SPACING;
+        if allocation.capacity > 0 && !heap.iter().any(|buffer| buffer.ptr == ptr) {
+            heap.push(Buffer { ptr, capacity: allocation.capacity, bytes: allocation.text.as_bytes().to_vec() });
+        }
+        ptr
+    }
+
+    // Fills the `{}` placeholders of a format string, `{{` and `}}` being escaped braces.
+    fn format(&self, format: &str, values: &[Value], line: usize) -> Result<String> {
+        let mut text = String::new();
+        let mut values = values.iter();
+        let mut chars = format.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            break;
+                        }
+                    }
+                    let value = values
+                        .next()
+                        .ok_or_else(|| Diagnostic::unsupported(line, "more `{}` placeholders than arguments".to_string()))?;
+                    text.push_str(&self.display(value));
+                }
+                c => text.push(c),
+            }
+        }
+        Ok(text)
+    }
+}
+
+// The value of an optional method argument, which is never moved.
+fn arg_value<'p>(machine: &mut Machine<'p>, arg: &'p [Expr]) -> Result<Option<Value>> {
+    match arg {
+        [] => Ok(None),
+        [arg] => machine.read(arg).map(Some),
+        _ => Err(Diagnostic::unsupported(arg[0].line().unwrap_or(0), "too many arguments".to_string())),
+    }
+}
+
+fn uninitialized(name: &str, line: usize) -> Diagnostic {
+    Diagnostic::new(line, "E0381", format!("used binding `{}` isn't initialized", name))
+}
+
+fn not_mutable(name: &str, line: usize) -> Diagnostic {
+    Diagnostic::new(line, "E0596", format!("cannot borrow `{}` as mutable, as it is not declared as mutable", name))
+        .note(format!("help: consider changing this to be mutable: `mut {}`", name))
+}
+
+fn pattern_name(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Name { name, .. } => name.clone(),
+        Pattern::Tuple(items) => format!("({})", items.iter().map(pattern_name).collect::<Vec<_>>().join(", ")),
+    }
+}
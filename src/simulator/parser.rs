@@ -0,0 +1,610 @@
+// Parses the small subset of Rust the simulator understands: functions, `let`, assignments, blocks,
+// calls, method calls, references, string slices, `println!`, `String::from`, tuples and literals. Everything else is reported as unsupported.
+
+use super::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Int(i64),
+    Str(String),
+    Punct(&'static str),
+}
+
+// Longest first, so `::` wins over `:`.
+const PUNCTUATION: &[&str] = &[
+    "::", "->", "..", "=>", "==", "!=", "(", ")", "{", "}", "[", "]", ";", ":", ",", ".", "=", "&", "!", "#", "<", ">",
+    "+", "-", "*", "/",
+];
+
+// The keywords of what the simulator doesn't run, like `loop` or `if`. Read as names, they would be variables
+// that don't exist, an E0425 that rustc would never give.
+const UNSUPPORTED_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "false", "fn", "for", "if", "impl", "in",
+    "loop", "match", "mod", "move", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, Error> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut rest = source;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '\n' {
+            line += 1;
+            rest = &rest[1..];
+        } else if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if rest.starts_with("//") {
+            rest = &rest[rest.find('\n').unwrap_or(rest.len())..];
+        } else if rest.starts_with("/*") {
+            let end = rest.find("*/").ok_or_else(|| Error::new(line, "unterminated block comment"))?;
+            line += rest[..end].matches('\n').count();
+            rest = &rest[end + 2..];
+        } else if c == '"' {
+            let (text, len) = string_literal(rest).ok_or_else(|| Error::new(line, "unterminated string literal"))?;
+            tokens.push((Token::Str(text), line));
+            line += rest[..len].matches('\n').count();
+            rest = &rest[len..];
+        } else if c.is_ascii_digit() {
+            let len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            let digits: String = rest[..len].chars().take_while(|c| c.is_ascii_digit() || *c == '_').filter(|c| *c != '_').collect();
+            let value = digits.parse().map_err(|_| Error::new(line, "integer literal is too large"))?;
+            tokens.push((Token::Int(value), line));
+            rest = &rest[len..];
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            tokens.push((Token::Ident(rest[..len].to_string()), line));
+            rest = &rest[len..];
+        } else if c == '\'' {
+            // Lifetimes, like the `'static` rustc suggests for `dangle`.
+            let len = 1 + rest[1..].find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len() - 1);
+            tokens.push((Token::Ident(rest[..len].to_string()), line));
+            rest = &rest[len..];
+        } else {
+            let punct = PUNCTUATION
+                .iter()
+                .find(|p| rest.starts_with(**p))
+                .ok_or_else(|| Error::new(line, format!("unexpected character `{}`", c)))?;
+            tokens.push((Token::Punct(punct), line));
+            rest = &rest[punct.len()..];
+        }
+    }
+
+    Ok(tokens)
+}
+
+// The contents of the string literal at the start of `rest`, and the length of the literal with its quotes.
+fn string_literal(rest: &str) -> Option<(String, usize)> {
+    let mut text = String::new();
+    let mut chars = rest.char_indices().skip(1);
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((text, i + 1)),
+            '\\' => match chars.next()?.1 {
+                'n' => text.push('\n'),
+                't' => text.push('\t'),
+                other => text.push(other),
+            },
+            c => text.push(c),
+        }
+    }
+    None
+}
+
+#[derive(Debug)]
+pub struct Program {
+    pub functions: Vec<Function>,
+    /// Statements written outside of any function, run in order.
+    pub statements: Vec<Stmt>,
+    /// The last line of the source, where the variables declared outside of functions are dropped.
+    pub end_line: usize,
+}
+
+#[derive(Debug)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub returns: Option<Type>,
+    pub body: Block,
+    pub line: usize,
+}
+
+#[derive(Debug)]
+pub struct Param {
+    pub name: String,
+    pub mutable: bool,
+    pub ty: Type,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Type {
+    Named(String),
+    Ref { mutable: bool, lifetime: Option<String>, inner: Box<Type> },
+    Tuple(Vec<Type>),
+}
+
+impl Type {
+    /// Whether the type is a reference or holds one.
+    pub fn has_reference(&self) -> bool {
+        match self {
+            Type::Named(_) => false,
+            Type::Ref { .. } => true,
+            Type::Tuple(items) => items.iter().any(Type::has_reference),
+        }
+    }
+
+    /// Whether the type holds a reference written without a lifetime, like `&String`.
+    pub fn has_elided_lifetime(&self) -> bool {
+        match self {
+            Type::Named(_) => false,
+            Type::Ref { lifetime: None, .. } => true,
+            Type::Ref { inner, .. } => inner.has_elided_lifetime(),
+            Type::Tuple(items) => items.iter().any(Type::has_elided_lifetime),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Block {
+    pub statements: Vec<Stmt>,
+    /// The expression the block evaluates to, written last without a `;`.
+    pub tail: Option<Box<Expr>>,
+    /// The line of the closing brace, where the locals are dropped.
+    pub end_line: usize,
+}
+
+#[derive(Debug)]
+pub enum Stmt {
+    Let { pattern: Pattern, value: Option<Expr>, line: usize },
+    Assign { name: String, value: Expr, line: usize },
+    Expr(Expr),
+}
+
+impl Stmt {
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Stmt::Let { line, .. } | Stmt::Assign { line, .. } => Some(*line),
+            Stmt::Expr(expr) => expr.line(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Pattern {
+    Name { name: String, mutable: bool },
+    Tuple(Vec<Pattern>),
+}
+
+#[derive(Debug)]
+pub enum Expr {
+    Int(i64),
+    /// A string literal, a `&'static str`.
+    Str(String),
+    /// `String::from("...")` or `"...".to_string()`.
+    NewString { text: String, line: usize },
+    Var { name: String, line: usize },
+    Call { name: String, args: Vec<Expr>, line: usize },
+    Method { receiver: Box<Expr>, method: String, args: Vec<Expr>, line: usize },
+    /// `&x` or `&mut x`.
+    Ref { mutable: bool, operand: Box<Expr>, line: usize },
+    /// `s[start..end]`, either bound left out, which is borrowed as `&s[start..end]`.
+    Slice { base: Box<Expr>, start: Option<Box<Expr>>, end: Option<Box<Expr>>, line: usize },
+    /// `println!` and `print!`, with the format string and its arguments.
+    Print { newline: bool, format: String, args: Vec<Expr>, line: usize },
+    Tuple(Vec<Expr>),
+    Block(Block),
+}
+
+impl Expr {
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Expr::NewString { line, .. }
+            | Expr::Var { line, .. }
+            | Expr::Call { line, .. }
+            | Expr::Method { line, .. }
+            | Expr::Ref { line, .. }
+            | Expr::Slice { line, .. }
+            | Expr::Print { line, .. } => Some(*line),
+            Expr::Tuple(items) => items.iter().find_map(Expr::line),
+            Expr::Int(_) | Expr::Str(_) | Expr::Block(_) => None,
+        }
+    }
+}
+
+pub fn parse(source: &str) -> Result<Program, Error> {
+    let tokens = tokenize(source)?;
+    let last_line = source.lines().count().max(1);
+    let mut parser = Parser { tokens, position: 0, last_line };
+
+    let mut program = Program { functions: Vec::new(), statements: Vec::new(), end_line: last_line };
+    while !parser.at_end() {
+        parser.skip_attributes()?;
+        if parser.peek_ident("fn") || parser.peek_ident("pub") {
+            program.functions.push(parser.function()?);
+        } else {
+            let (stmt, tail) = parser.statement()?;
+            program.statements.extend(stmt);
+            program.statements.extend(tail.map(Stmt::Expr));
+        }
+    }
+
+    Ok(program)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    last_line: usize,
+}
+
+impl Parser {
+    fn at_end(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.position + offset).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        match self.tokens.get(self.position) {
+            Some((_, line)) => *line,
+            None => self.last_line,
+        }
+    }
+
+    fn peek_ident(&self, ident: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(i)) if i == ident)
+    }
+
+    fn peek_punct(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punct(p)) if *p == punct)
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        if self.peek_punct(punct) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_ident(&mut self, ident: &str) -> bool {
+        if self.peek_ident(ident) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<(), Error> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{}`", punct)))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, Error> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                self.position += 1;
+                Ok(ident)
+            }
+            _ => Err(self.unexpected("a name")),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> Error {
+        let found = match self.peek() {
+            Some(Token::Ident(ident)) => format!("`{}`", ident),
+            Some(Token::Int(value)) => format!("`{}`", value),
+            Some(Token::Str(text)) => format!("{:?}", text),
+            Some(Token::Punct(punct)) => format!("`{}`", punct),
+            None => "the end of the code".to_string(),
+        };
+        Error::new(self.line(), format!("expected {}, found {}", expected, found))
+    }
+
+    // `#[...]` attributes don't change ownership, so they are skipped.
+    fn skip_attributes(&mut self) -> Result<(), Error> {
+        while self.peek_punct("#") {
+            self.position += 1;
+            self.eat_punct("!");
+            self.expect_punct("[")?;
+            let mut depth = 1;
+            while depth > 0 {
+                match self.peek() {
+                    Some(Token::Punct("[")) => depth += 1,
+                    Some(Token::Punct("]")) => depth -= 1,
+                    None => return Err(self.unexpected("`]`")),
+                    _ => {}
+                }
+                self.position += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn function(&mut self) -> Result<Function, Error> {
+        self.eat_ident("pub");
+        let line = self.line();
+        if !self.eat_ident("fn") {
+            return Err(self.unexpected("`fn`"));
+        }
+        let name = self.expect_ident()?;
+
+        self.expect_punct("(")?;
+        let mut params = Vec::new();
+        while !self.eat_punct(")") {
+            let mutable = self.eat_ident("mut");
+            let name = self.expect_ident()?;
+            self.expect_punct(":")?;
+            let ty = self.ty()?;
+            params.push(Param { name, mutable, ty });
+            if !self.eat_punct(",") {
+                self.expect_punct(")")?;
+                break;
+            }
+        }
+
+        let returns = if self.eat_punct("->") { Some(self.ty()?) } else { None };
+        let body = self.block()?;
+
+        Ok(Function { name, params, returns, body, line })
+    }
+
+    fn ty(&mut self) -> Result<Type, Error> {
+        if self.eat_punct("&") {
+            let lifetime = match self.peek() {
+                Some(Token::Ident(ident)) if ident.starts_with('\'') => {
+                    let lifetime = ident.clone();
+                    self.position += 1;
+                    Some(lifetime)
+                }
+                _ => None,
+            };
+            let mutable = self.eat_ident("mut");
+            let inner = Box::new(self.ty()?);
+            return Ok(Type::Ref { mutable, lifetime, inner });
+        }
+
+        if self.eat_punct("(") {
+            let mut items = Vec::new();
+            while !self.eat_punct(")") {
+                items.push(self.ty()?);
+                if !self.eat_punct(",") {
+                    self.expect_punct(")")?;
+                    break;
+                }
+            }
+            return Ok(Type::Tuple(items));
+        }
+
+        let mut name = self.expect_ident()?;
+        if self.eat_punct("[") {
+            // Slices, like `&[i32]`.
+            let inner = self.expect_ident()?;
+            self.expect_punct("]")?;
+            name = format!("[{}]", inner);
+        }
+        Ok(Type::Named(name))
+    }
+
+    fn block(&mut self) -> Result<Block, Error> {
+        self.expect_punct("{")?;
+        let mut statements = Vec::new();
+        let mut tail = None;
+
+        loop {
+            if self.peek_punct("}") {
+                let end_line = self.line();
+                self.position += 1;
+                return Ok(Block { statements, tail: tail.map(Box::new), end_line });
+            }
+            if self.at_end() {
+                return Err(self.unexpected("`}`"));
+            }
+            if let Some(previous) = tail.take() {
+                // Only the last expression of a block can go without a `;`, unless it is a block itself.
+                match previous {
+                    Expr::Block(_) => statements.push(Stmt::Expr(previous)),
+                    _ => return Err(self.unexpected("`;` or `}`")),
+                }
+            }
+
+            let (stmt, expr) = self.statement()?;
+            statements.extend(stmt);
+            tail = expr;
+        }
+    }
+
+    // A statement, or an expression without `;` that may be the tail of its block.
+    fn statement(&mut self) -> Result<(Option<Stmt>, Option<Expr>), Error> {
+        self.skip_attributes()?;
+        let line = self.line();
+
+        if self.eat_ident("let") {
+            let pattern = self.pattern()?;
+            if self.eat_punct(":") {
+                self.ty()?;
+            }
+            let value = if self.eat_punct("=") { Some(self.expr()?) } else { None };
+            self.expect_punct(";")?;
+            return Ok((Some(Stmt::Let { pattern, value, line }), None));
+        }
+
+        if let (Some(Token::Ident(name)), Some(Token::Punct("="))) = (self.peek(), self.peek_at(1)) {
+            let name = name.clone();
+            self.position += 2;
+            let value = self.expr()?;
+            self.expect_punct(";")?;
+            return Ok((Some(Stmt::Assign { name, value, line }), None));
+        }
+
+        let expr = self.expr()?;
+        if self.eat_punct(";") {
+            while self.eat_punct(";") {}
+            Ok((Some(Stmt::Expr(expr)), None))
+        } else {
+            Ok((None, Some(expr)))
+        }
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, Error> {
+        if self.eat_punct("(") {
+            let mut items = Vec::new();
+            while !self.eat_punct(")") {
+                items.push(self.pattern()?);
+                if !self.eat_punct(",") {
+                    self.expect_punct(")")?;
+                    break;
+                }
+            }
+            return Ok(Pattern::Tuple(items));
+        }
+
+        let mutable = self.eat_ident("mut");
+        let name = self.expect_ident()?;
+        Ok(Pattern::Name { name, mutable })
+    }
+
+    fn expr(&mut self) -> Result<Expr, Error> {
+        let mut expr = self.primary()?;
+
+        loop {
+            if self.peek_punct("[") {
+                expr = self.slice(expr)?;
+            } else if self.eat_punct(".") {
+                let line = self.line();
+                let method = self.expect_ident()?;
+                let args = self.arguments()?;
+                expr = match (expr, method.as_str()) {
+                    (Expr::Str(text), "to_string") | (Expr::Str(text), "to_owned") => Expr::NewString { text, line },
+                    (receiver, _) => Expr::Method { receiver: Box::new(receiver), method, args, line },
+                };
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, Error> {
+        let line = self.line();
+        match self.peek().cloned() {
+            Some(Token::Int(value)) => {
+                self.position += 1;
+                Ok(Expr::Int(value))
+            }
+            Some(Token::Str(text)) => {
+                self.position += 1;
+                Ok(Expr::Str(text))
+            }
+            Some(Token::Punct("{")) => Ok(Expr::Block(self.block()?)),
+            Some(Token::Punct("&")) => {
+                self.position += 1;
+                let mutable = self.eat_ident("mut");
+                let operand = Box::new(self.expr()?);
+                Ok(Expr::Ref { mutable, operand, line })
+            }
+            Some(Token::Punct("(")) => {
+                self.position += 1;
+                let mut items = Vec::new();
+                while !self.eat_punct(")") {
+                    items.push(self.expr()?);
+                    if !self.eat_punct(",") {
+                        self.expect_punct(")")?;
+                        break;
+                    }
+                }
+                if items.len() == 1 {
+                    Ok(items.remove(0))
+                } else {
+                    Ok(Expr::Tuple(items))
+                }
+            }
+            Some(Token::Ident(name)) if UNSUPPORTED_KEYWORDS.contains(&name.as_str()) => {
+                Err(Error::new(line, format!("`{}` is not supported", name)))
+            }
+            Some(Token::Ident(name)) => {
+                self.position += 1;
+
+                if self.eat_punct("::") {
+                    let function = self.expect_ident()?;
+                    if name == "String" && (function == "from" || function == "new") {
+                        let mut args = self.arguments()?;
+                        return match (args.pop(), args.is_empty()) {
+                            (Some(Expr::Str(text)), true) => Ok(Expr::NewString { text, line }),
+                            (None, _) if function == "new" => Ok(Expr::NewString { text: String::new(), line }),
+                            _ => Err(Error::new(line, "`String::from` takes a single string literal here")),
+                        };
+                    }
+                    return Err(Error::new(line, format!("`{}::{}` is not supported", name, function)));
+                }
+
+                if self.eat_punct("!") {
+                    return self.macro_call(name, line);
+                }
+
+                if self.peek_punct("(") {
+                    let args = self.arguments()?;
+                    return Ok(Expr::Call { name, args, line });
+                }
+
+                Ok(Expr::Var { name, line })
+            }
+            _ => Err(self.unexpected("an expression")),
+        }
+    }
+
+    // The range after `base`, only ranges: `[0..5]`, `[..5]`, `[6..]` or `[..]`.
+    fn slice(&mut self, base: Expr) -> Result<Expr, Error> {
+        let line = self.line();
+        self.expect_punct("[")?;
+        let start = if self.peek_punct("..") { None } else { Some(Box::new(self.expr()?)) };
+        self.expect_punct("..")?;
+        let end = if self.peek_punct("]") { None } else { Some(Box::new(self.expr()?)) };
+        self.expect_punct("]")?;
+        Ok(Expr::Slice { base: Box::new(base), start, end, line })
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Expr>, Error> {
+        self.expect_punct("(")?;
+        let mut args = Vec::new();
+        while !self.eat_punct(")") {
+            args.push(self.expr()?);
+            if !self.eat_punct(",") {
+                self.expect_punct(")")?;
+                break;
+            }
+        }
+        Ok(args)
+    }
+
+    fn macro_call(&mut self, name: String, line: usize) -> Result<Expr, Error> {
+        let newline = match name.as_str() {
+            "println" => true,
+            "print" => false,
+            _ => return Err(Error::new(line, format!("the `{}!` macro is not supported", name))),
+        };
+
+        let mut args = self.arguments()?;
+        if args.is_empty() {
+            return Ok(Expr::Print { newline, format: String::new(), args, line });
+        }
+        match args.remove(0) {
+            Expr::Str(format) => Ok(Expr::Print { newline, format, args, line }),
+            _ => Err(Error::new(line, format!("`{}!` needs a format string", name))),
+        }
+    }
+}
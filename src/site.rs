@@ -0,0 +1,292 @@
+// Static Site ---
+// The same sections as the book, rendered to plain HTML pages that need nothing but a browser: the styles are in
+// every page, the code is highlighted here rather than by a script, and the memory diagrams are inline SVG.
+// An example that doesn't compile gets a red border, its error codes, and a mark on the lines they point at.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::book::{self, Block};
+use crate::compile_fail::Annotation;
+use crate::diagram;
+use crate::lesson::{self, Chapter};
+
+const TITLE: &str = "Understanding Ownership";
+
+// The diagrams bring their own styles for `.title`, `.frame`, `.slot`, `.code` and a few more, which apply
+// to the whole page once they are inline, so the classes of the page itself must not use those names.
+const STYLE: &str = "
+body { max-width: 860px; margin: 2em auto; padding: 0 1em; font-family: Georgia, serif; line-height: 1.5; color: #222; }
+nav { display: flex; justify-content: space-between; font-family: sans-serif; font-size: 0.9em; }
+h1, h2 { font-family: sans-serif; }
+h2 { margin-top: 2em; border-bottom: 1px solid #ddd; }
+pre { background: #f6f8fa; padding: 0.8em 1em; overflow-x: auto; border-radius: 4px; }
+pre, code { font-family: Menlo, Consolas, monospace; font-size: 14px; }
+.kw { color: #a626a4; }
+.ty { color: #c18401; }
+.str { color: #50a14f; }
+.num { color: #986801; }
+.mac { color: #4078f2; }
+.lt { color: #e45649; }
+.com { color: #8a8f98; font-style: italic; }
+figure { margin: 1.5em 0; }
+.fails { border-left: 4px solid #d73a49; padding-left: 0.8em; }
+.fails figcaption { font-family: sans-serif; color: #d73a49; }
+.error-code { display: inline-block; background: #d73a49; color: #fff; padding: 0 0.4em; margin-right: 0.4em; border-radius: 3px; font-family: monospace; }
+.marked { background: #ffeef0; display: inline-block; min-width: 100%; }
+.marked::after { content: \"  \\2190  \" attr(data-code); color: #d73a49; font-weight: bold; }
+.diagrams figcaption { font-family: sans-serif; font-size: 0.9em; color: #555; }
+.diagrams svg { max-width: 100%; height: auto; }
+";
+
+/// The name of the page of a chapter.
+pub fn file_name(chapter: &Chapter) -> String {
+    format!("{}.html", chapter.module)
+}
+
+/// The page of a chapter, with links to the chapters around it.
+pub fn page(chapter: &Chapter) -> String {
+    let position = lesson::CHAPTERS.iter().position(|other| other.module == chapter.module).unwrap_or(0);
+    let previous = match position.checked_sub(1).map(|i| lesson::CHAPTERS[i]) {
+        Some(previous) => format!("<a href=\"{}\">&larr; {}</a>", file_name(previous), escape(previous.title)),
+        None => "<span></span>".to_string(),
+    };
+    let next = match lesson::CHAPTERS.get(position + 1) {
+        Some(next) => format!("<a href=\"{}\">{} &rarr;</a>", file_name(next), escape(next.title)),
+        None => "<span></span>".to_string(),
+    };
+    let nav = format!("<nav>{}<a href=\"index.html\">Contents</a>{}</nav>\n", previous, next);
+
+    let mut body = format!("{}<h1>{}</h1>\n", nav, escape(chapter.title));
+    for section in book::sections(chapter) {
+        if let Some(heading) = &section.heading {
+            let _ = writeln!(body, "<h2 id=\"{}\">{}</h2>", book::anchor(heading), escape(heading));
+        }
+        for block in &section.blocks {
+            match block {
+                Block::Prose(text) => body.push_str(&prose(text)),
+                Block::Code(code) => {
+                    let _ = writeln!(body, "<pre><code>{}</code></pre>", highlight(code, &[]));
+                    body.push_str(&diagrams(chapter, code));
+                }
+                Block::Fails { code, errors } => {
+                    body.push_str("<figure class=\"fails\">\n<figcaption>This doesn't compile:");
+                    for error in errors {
+                        let _ = write!(body, "<br><span class=\"error-code\">{}</span>{}", escape(&error.code), inline(&error.message));
+                    }
+                    let _ = writeln!(body, "</figcaption>\n<pre><code>{}</code></pre>\n</figure>", highlight(code, errors));
+                }
+            }
+        }
+    }
+    body.push_str(&nav);
+    document(&format!("{} - {}", chapter.title, TITLE), &body)
+}
+
+/// The first page: every section of every chapter.
+pub fn index() -> String {
+    let mut body = format!("<h1>{}</h1>\n<p>Notes on ownership, borrowing and slices, with the examples they explain.</p>\n<ol>\n", TITLE);
+    for chapter in lesson::CHAPTERS.iter() {
+        let _ = writeln!(body, "<li><a href=\"{}\">{}</a>\n<ul>", file_name(chapter), escape(chapter.title));
+        for heading in book::sections(chapter).iter().filter_map(|section| section.heading.as_ref()) {
+            let _ = writeln!(body, "<li><a href=\"{}#{}\">{}</a></li>", file_name(chapter), book::anchor(heading), escape(heading));
+        }
+        body.push_str("</ul></li>\n");
+    }
+    body.push_str("</ol>\n");
+    document(TITLE, &body)
+}
+
+/// Writes `index.html` and a page per chapter to `dir`, and returns the files it wrote.
+pub fn write(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+
+    let mut files = vec![(dir.join("index.html"), index())];
+    for chapter in lesson::CHAPTERS.iter() {
+        files.push((dir.join(file_name(chapter)), page(chapter)));
+    }
+    for (path, contents) in &files {
+        fs::write(path, contents)?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+fn document(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        STYLE,
+        body
+    )
+}
+
+// Paragraphs, with a list for the lines that start with `- ` and `code` for what is between backticks.
+fn prose(text: &str) -> String {
+    let mut html = String::new();
+    let mut in_list = false;
+    for line in text.lines() {
+        match line.strip_prefix("- ") {
+            Some(item) => {
+                if !in_list {
+                    html.push_str("<ul>\n");
+                    in_list = true;
+                }
+                let _ = writeln!(html, "<li>{}</li>", inline(item));
+            }
+            None => {
+                if in_list {
+                    html.push_str("</ul>\n");
+                    in_list = false;
+                }
+                let _ = writeln!(html, "<p>{}</p>", inline(line));
+            }
+        }
+    }
+    if in_list {
+        html.push_str("</ul>\n");
+    }
+    // The lines of a paragraph were wrapped by hand, they read as one.
+    html.replace("</p>\n<p>", "\n")
+}
+
+fn inline(text: &str) -> String {
+    text.split('`')
+        .enumerate()
+        .map(|(i, part)| if i % 2 == 1 { format!("<code>{}</code>", escape(part)) } else { escape(part) })
+        .collect()
+}
+
+// The snapshots of the diagrams drawn from the functions of `code`, one figure per line.
+fn diagrams(chapter: &Chapter, code: &str) -> String {
+    let mut html = String::new();
+    for diagram in diagram::DIAGRAMS {
+        let name = match diagram.lesson.strip_prefix(chapter.module).and_then(|rest| rest.strip_prefix("::")) {
+            Some(name) => name,
+            None => continue,
+        };
+        if !code.lines().any(|line| lesson::is_signature_of(line, name)) {
+            continue;
+        }
+
+        // Every lesson with a diagram simulates without an error, tests/diagram.rs checks it.
+        let snapshots = diagram.snapshots().unwrap_or_default();
+        html.push_str("<div class=\"diagrams\">\n");
+        for (i, snapshot) in snapshots.iter().enumerate() {
+            let _ = writeln!(html, "<figure>\n<figcaption>after <code>{}</code></figcaption>", escape(&snapshot.code));
+            html.push_str(&snapshot.to_svg(&format!("{}-{}", diagram.lesson.replace("::", "-"), i + 1)));
+            html.push_str("</figure>\n");
+        }
+        html.push_str("</div>\n");
+    }
+    html
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Highlighting ---
+// Enough of a Rust lexer for the examples: keywords, types, macros, literals, lifetimes and `//` comments.
+// It works a line at a time, as none of the examples have a string or a comment that spans lines.
+
+const KEYWORDS: &[&str] = &[
+    "as", "break", "const", "continue", "crate", "dyn", "else", "enum", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match",
+    "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while",
+];
+
+/// `code` as HTML, with a `<span>` of a class for each kind of token.
+/// The lines some of `errors` point at are marked with their error codes.
+pub fn highlight(code: &str, errors: &[Annotation]) -> String {
+    let lines: Vec<String> = code
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let codes: Vec<&str> = errors.iter().filter(|error| error.line == i + 1).map(|error| error.code.as_str()).collect();
+            if codes.is_empty() {
+                highlight_line(line)
+            } else {
+                format!("<span class=\"marked\" data-code=\"{}\">{}</span>", codes.join(" "), highlight_line(line))
+            }
+        })
+        .collect();
+    lines.join("\n")
+}
+
+fn highlight_line(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut html = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let class = if c == '/' && chars.get(i + 1) == Some(&'/') {
+            i = chars.len();
+            "com"
+        } else if c == '"' || (c == 'b' && chars.get(i + 1) == Some(&'"')) {
+            i = end_of_quoted(&chars, if c == 'b' { i + 1 } else { i }, '"');
+            "str"
+        } else if c == '\'' || (c == 'b' && chars.get(i + 1) == Some(&'\'')) {
+            let quote = if c == 'b' { i + 1 } else { i };
+            // 'a' and '\n' are chars, 'a on its own is a lifetime.
+            let escaped = chars.get(quote + 1) == Some(&'\\');
+            if escaped || chars.get(quote + 2) == Some(&'\'') {
+                i = end_of_quoted(&chars, quote, '\'');
+                "str"
+            } else {
+                i = quote + 1;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                "lt"
+            }
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            "num"
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            if chars.get(i) == Some(&'!') && chars.get(i + 1) != Some(&'=') {
+                i += 1;
+                "mac"
+            } else if KEYWORDS.contains(&word.as_str()) {
+                "kw"
+            } else if c.is_uppercase() {
+                "ty"
+            } else {
+                ""
+            }
+        } else {
+            i += 1;
+            ""
+        };
+
+        let token: String = chars[start..i].iter().collect();
+        if class.is_empty() {
+            html.push_str(&escape(&token));
+        } else {
+            let _ = write!(html, "<span class=\"{}\">{}</span>", class, escape(&token));
+        }
+    }
+    html
+}
+
+// The index after the quote that closes the literal opened at `open`, or the end of the line.
+fn end_of_quoted(chars: &[char], open: usize, quote: char) -> usize {
+    let mut i = open + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
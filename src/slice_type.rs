@@ -1,8 +1,45 @@
+use crate::checked_slice::{checked_slice, snapped_slice};
+use crate::lesson::{Chapter, Lesson};
+
 // The Slice Type -------------------------------------------------------------
 
 // Another data type that does not have ownership is the slice.
 // Slices let you reference a contiguous sequence of elements in a collection rather than the whole collection.
 
+// Without Slices ---
+// Without slices, first_word could only return the index of the end of the word, that is, the index of the first space:
+pub fn first_word_index(s: &String) -> usize {
+    let bytes = s.as_bytes();
+
+    for (i, &item) in bytes.iter().enumerate() {
+        if item == b' ' {
+            return i;
+        }
+    }
+
+    s.len()
+}
+
+// The index is a number that means something only in the context of the String it came from,
+// and nothing ties the two together: the String can change while the number stays the same.
+fn main_index() {
+    let mut s = String::from("hello world");
+
+    let word = first_word_index(&s); // word will get the value 5
+
+    s.clear(); // this empties the String, making it equal to ""
+
+    // word still has the value 5 here, but there's no more string that we could meaningfully use the value 5 with.
+    // word is now totally invalid! &s[0..word] would panic, get at least tells us there is nothing there:
+    println!("after clear, s[0..{}] is {:?}", word, s.get(0..word));
+
+    // Worse, once the String has new contents, the stale index slices them without any complaint:
+    s.push_str("goodbye world");
+    println!("the first word is: {}", &s[0..word]); // goodb, not goodbye
+}
+// This compiles and runs, and the bug only shows in the output. The slice version of this same code, slice_type::main
+// below, doesn't compile at all: the borrow checker sees that word still borrows s when s.clear() needs to mutate it.
+
 // String Slices ---
 // A string slice is a reference to part of a String, and it looks like this:
 fn a() {
@@ -77,34 +114,28 @@ fn first_word(s: &String) -> &str {
 
 // If we try to use that function and after that, modify the variable s we will get an error.
 // We can't modify a mutable reference if we have an immutable borrow of the same variable.
-fn main() {
-    let mut s = String::from("hello world");
+#[cfg(feature = "show-errors")]
+#[path = "../tests/compile-fail/slice_type/main.rs"]
+mod main;
 
-    let word = first_word(&s); // -- immutable borrow occurs here
-
-    s.clear(); // error! error[E0502]: cannot borrow `s` as mutable because it is also borrowed as immutable
-    // ^^^^^^^^^ mutable borrow occurs here
+// Here is another similar example
+#[cfg(feature = "show-errors")]
+#[path = "../tests/compile-fail/slice_type/main_same.rs"]
+mod main_same;
 
-    println!("the first word is: {}", word); // ---- immutable borrow later used here
-}
+// "IF WE HAVE AN IMMUTABLE REFERENCE TO SOMETHING, WE CANNOT ALSO TAKE A MUTABLE REFERENCE."
 
-// Here is another similar example
-fn main_same() {
-    let mut mutable = String::from("Hello");
+// The fix is to be done with the slice before the String changes: the borrow ends at the last use of word.
+fn main_fixed() {
+    let mut s = String::from("hello world");
 
-    let immutable_reference = &mutable; // -------- immutable borrow occurs here
+    let word = first_word(&s);
 
-    mutable.clear(); // here we try to modify the mutable, but we can't because we already have an immutable reference of the same variable
-    // the method clear() needs a mutable value, because it will truncate the String
-    // ^^^^^^^^^^^^^^^ mutable borrow occurs here
+    println!("the first word is: {}", word); // word is last used here, s is no longer borrowed after this line
 
-    println!("{}", immutable_reference); // if this wasn't here, the code will be okay.
-                                        // But we need to use the immutable_reference again and the "main" reference was already "deleted"
-    // ------------------- immutable borrow later used here
+    s.clear(); // no problem, this empties the String and nothing points into it anymore
 }
 
-// "IF WE HAVE AN IMMUTABLE REFERENCE TO SOMETHING, WE CANNOT ALSO TAKE A MUTABLE REFERENCE."
-
 // String Literals Are Slices ---
 
 // Recall that we talked about string literals being stored inside the binary.
@@ -117,10 +148,24 @@ fn literal() {
 
 // String Slices as Parameters ---
 // Knowing that you can take slices of literals and String values leads us to one more improvement on first_word, and that’s its signature:
-fn first_word_signature(s: &String) -> &str {}
+// Its body is the one of first_word, which is written once below, for a &str: a slice of the entire String is one.
+pub fn first_word_signature(s: &String) -> &str {
+    first_word_better_signature(&s[..])
+}
 
 // A more experienced Rustacean would write the next signature instead because it allows us to use the same function on both &String values and &str values.
-fn first_word_better_signature(s: &str) -> &str {}
+// The body of first_word doesn't change at all: indexing and as_bytes work the same on a &str.
+pub fn first_word_better_signature(s: &str) -> &str {
+    let bytes = s.as_bytes();
+
+    for (i, &item) in bytes.iter().enumerate() {
+        if item == b' ' {
+            return &s[0..i];
+        }
+    }
+
+    &s[..]
+}
 
 // If we have a string slice, we can pass that directly. If we have a String, we can pass a slice of the entire String.
 // Defining a function to take a string slice instead of a reference to a String makes our API more general and useful without losing any functionality:
@@ -138,6 +183,11 @@ fn main_a() {
     // Because string literals *are* string slices already,
     // this works too, without the slice syntax!
     let word = first_word_better_signature(my_string_literal);
+
+    // And a &String works as well: deref coercion turns it into a &str, the same way it turns a &Box<str> or a &Cow<str> into one.
+    let word = first_word_better_signature(&my_string);
+
+    println!("{}", word);
 }
 
 // Other Slices ---
@@ -152,4 +202,92 @@ fn g() {
 
     let slice = &a[1..3]; // [2, 3]
 }
-// This slice has the type &[i32]. It works the same way as string slices do, by storing a reference to the first element and a length.
\ No newline at end of file
+// This slice has the type &[i32]. It works the same way as string slices do, by storing a reference to the first element and a length.
+
+// Words Beyond ASCII ---
+// first_word only looks for the byte b' ', so "hello\tworld" is a single word to it, and so is "hello\u{a0}world" with a non-breaking space.
+// Real text can be split by any Unicode whitespace, and most of those characters take more than one byte in UTF-8.
+// char_indices goes through the string one char at a time and gives the byte index where each char starts,
+// so every index it gives is a char boundary, and slicing there can't cut a character in half.
+pub fn first_word_unicode(s: &str) -> &str {
+    // Unlike first_word, whitespace before the first word is skipped: "  hello" starts with the word hello, not with an empty word.
+    let start = match s.char_indices().find(|(_, c)| !c.is_whitespace()) {
+        Some((start, _)) => start,
+        None => return "",
+    };
+
+    // The word ends at the next whitespace char, or at the end of the string.
+    // Accents written as combining marks and emoji joined with a zero width joiner are not whitespace, so they stay whole.
+    match s[start..].char_indices().find(|(_, c)| c.is_whitespace()) {
+        Some((end, _)) => &s[start..start + end],
+        None => &s[start..],
+    }
+}
+
+// Slicing Without Panicking ---
+// a() to d() only slice "hello", where every byte is a char. In "Здравствуйте" every letter takes two bytes,
+// so &s[0..1] would cut the first letter in half and the program would panic, and so would &s[0..100] or &s[4..2].
+// checked_slice takes the same ranges and returns an error instead, snapped_slice widens the range to whole letters.
+fn checked() {
+    let s = String::from("Здравствуйте");
+
+    for (start, end) in [(0, 4), (0, 1), (0, 100), (4, 2)] {
+        match checked_slice(&s, start..end) {
+            Ok(slice) => println!("&s[{}..{}] is {:?}", start, end, slice),
+            Err(error) => println!("&s[{}..{}] would panic: {}", start, end, error),
+        }
+    }
+
+    println!("snapped to whole letters, 1..3 is {:?}", snapped_slice(&s, 1..3));
+}
+
+pub const CHAPTER: Chapter = Chapter {
+    module: "slice_type",
+    source: include_str!("slice_type.rs"),
+    title: "The Slice Type",
+    lessons: &[
+        Lesson {
+            name: "first_word_index",
+            section: "Without Slices",
+            title: "Returning the index of the end of the first word",
+            run: Some(|| println!("{}", first_word_index(&String::from("hello world")))),
+        },
+        Lesson { name: "main_index", section: "Without Slices", title: "An index that goes stale after clear", run: Some(main_index) },
+        Lesson { name: "a", section: "String Slices", title: "Taking a slice of part of a String", run: Some(a) },
+        Lesson { name: "b", section: "String Slices", title: "Dropping the start index of a range", run: Some(b) },
+        Lesson { name: "c", section: "String Slices", title: "Dropping the end index of a range", run: Some(c) },
+        Lesson { name: "d", section: "String Slices", title: "Slicing the whole string", run: Some(d) },
+        Lesson {
+            name: "first_word",
+            section: "String Slices",
+            title: "Returning the first word as a slice",
+            run: Some(|| println!("{}", first_word(&String::from("hello world")))),
+        },
+        Lesson { name: "main", section: "String Slices", title: "Clearing a String while a slice of it is in use", run: None },
+        Lesson { name: "main_same", section: "String Slices", title: "Mutating a String while it is borrowed", run: None },
+        Lesson { name: "main_fixed", section: "String Slices", title: "Using the slice before clearing the String", run: Some(main_fixed) },
+        Lesson { name: "literal", section: "String Literals Are Slices", title: "String literals are &str", run: Some(literal) },
+        Lesson {
+            name: "first_word_signature",
+            section: "String Slices as Parameters",
+            title: "first_word returning a string slice",
+            run: Some(|| println!("{}", first_word_signature(&String::from("hello world")))),
+        },
+        Lesson {
+            name: "first_word_better_signature",
+            section: "String Slices as Parameters",
+            title: "first_word taking a string slice",
+            run: Some(|| println!("{}", first_word_better_signature("hello world"))),
+        },
+        Lesson { name: "main_a", section: "String Slices as Parameters", title: "Taking &str works for both String and literals", run: Some(main_a) },
+        Lesson { name: "f", section: "Other Slices", title: "An array of integers", run: Some(f) },
+        Lesson { name: "g", section: "Other Slices", title: "Taking a slice of an array", run: Some(g) },
+        Lesson {
+            name: "first_word_unicode",
+            section: "Words Beyond ASCII",
+            title: "Splitting on any Unicode whitespace",
+            run: Some(|| println!("{}", first_word_unicode("\tcafé\u{a0}crème"))),
+        },
+        Lesson { name: "checked", section: "Slicing Without Panicking", title: "Slicing that returns errors instead", run: Some(checked) },
+    ],
+};
@@ -0,0 +1,57 @@
+// Slices of Anything ---
+// `slice_type::g` takes `&a[1..3]` of an array of i32 and stops there, but everything first_word does with a string
+// works on a `&[T]` too: a slice of a byte buffer or of an array of numbers is a pointer and a length just the same.
+// Every function here returns slices of the slice it is given, so nothing is copied, and the results can't outlive it.
+
+/// The elements before the first `separator`, or the whole slice if there is none: first_word for any `T`.
+pub fn first_word<'a, T: PartialEq>(s: &'a [T], separator: &T) -> &'a [T] {
+    for (i, item) in s.iter().enumerate() {
+        if item == separator {
+            return &s[..i];
+        }
+    }
+
+    s
+}
+
+/// The non-empty runs of elements between separators, the way `words` splits a string on whitespace.
+pub fn words<'a, T: PartialEq>(s: &'a [T], separator: &'a T) -> impl Iterator<Item = &'a [T]> + 'a {
+    s.split(move |item| item == separator).filter(|word| !word.is_empty())
+}
+
+/// The longest start of `s` whose elements all match `pred`. Empty if the first one doesn't.
+pub fn first_run<T>(s: &[T], mut pred: impl FnMut(&T) -> bool) -> &[T] {
+    let end = s.iter().position(|item| !pred(item)).unwrap_or(s.len());
+    &s[..end]
+}
+
+/// The elements before and after the first `value`, which is in neither. `None` if `value` isn't in `s`.
+pub fn split_at_value<'a, T: PartialEq>(s: &'a [T], value: &T) -> Option<(&'a [T], &'a [T])> {
+    let i = s.iter().position(|item| item == value)?;
+    Some((&s[..i], &s[i + 1..]))
+}
+
+/// Where `needle` first appears in `s`, like `str::find` for slices: `b"\r\n"` in a byte buffer, for example.
+///
+/// An empty needle is found at 0.
+pub fn find_slice<T: PartialEq>(s: &[T], needle: &[T]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    s.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Every element with the one after it.
+pub fn pairs<T>(s: &[T]) -> impl Iterator<Item = (&T, &T)> {
+    s.windows(2).map(|pair| (&pair[0], &pair[1]))
+}
+
+/// The first window of `size` elements that matches `pred`, as a slice of `s`.
+///
+/// `None` if no window does, or if `size` is 0.
+pub fn find_window<T>(s: &[T], size: usize, mut pred: impl FnMut(&[T]) -> bool) -> Option<&[T]> {
+    if size == 0 {
+        return None;
+    }
+    s.windows(size).find(|window| pred(window))
+}
@@ -1,3 +1,5 @@
+use crate::lesson::{Chapter, Lesson};
+
 fn main() {
     // The Stack and the Heap -------------------------------------------------------------------
 
@@ -40,4 +42,13 @@ fn main() {
     // Once you understand ownership, you won’t need to think about the stack and the heap very often,
 
     // but knowing that managing heap data is why ownership exists can help explain why it works the way it does.
-}
\ No newline at end of file
+}
+
+pub const CHAPTER: Chapter = Chapter {
+    module: "the_stack_and_the_heap",
+    source: include_str!("the_stack_and_the_heap.rs"),
+    title: "The Stack and the Heap",
+    lessons: &[
+        Lesson { name: "main", section: "The Stack and the Heap", title: "How the stack and the heap are structured", run: Some(main) },
+    ],
+};
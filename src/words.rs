@@ -0,0 +1,81 @@
+// Words ---
+// The next step after first_word: the book leaves second_word as an exercise, and the answer generalizes to any word.
+// Every function here returns slices of the string it is given, so nothing is copied, and the words can't outlive the string.
+// Words are separated by any Unicode whitespace, the way `slice_type::first_word_unicode` finds them.
+
+use std::ops::Range;
+
+use crate::slice_type::first_word_unicode;
+
+/// The words of `s`, each with the byte range it has in `s`, so `&s[range] == word`.
+pub fn words(s: &str) -> Words<'_> {
+    Words { source: s, front: 0, back: s.len() }
+}
+
+/// The word at index `n`, counting from 0. `None` if `s` has `n` words or less.
+pub fn nth_word(s: &str, n: usize) -> Option<&str> {
+    words(s).nth(n).map(|(_, word)| word)
+}
+
+/// The book's exercise. `None` if `s` has less than two words.
+pub fn second_word(s: &str) -> Option<&str> {
+    nth_word(s, 1)
+}
+
+pub fn last_word(s: &str) -> Option<&str> {
+    words(s).next_back().map(|(_, word)| word)
+}
+
+/// Iterator over the words of a string, see `words`.
+#[derive(Clone, Debug)]
+pub struct Words<'a> {
+    source: &'a str,
+    // The part of `source` that is left, as byte indexes: always char boundaries.
+    front: usize,
+    back: usize,
+}
+
+impl<'a> Words<'a> {
+    // Where `word` is in `source`. A slice is a pointer and a length, so the pointer says where it starts.
+    fn range_of(&self, word: &'a str) -> Range<usize> {
+        let start = word.as_ptr() as usize - self.source.as_ptr() as usize;
+        start..start + word.len()
+    }
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = (Range<usize>, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let word = first_word_unicode(&self.source[self.front..self.back]);
+        if word.is_empty() {
+            self.front = self.back;
+            return None;
+        }
+
+        let range = self.range_of(word);
+        self.front = range.end;
+        Some((range, word))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Words<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let rest = self.source[self.front..self.back].trim_end();
+        if rest.is_empty() {
+            self.back = self.front;
+            return None;
+        }
+
+        // The last word starts after the last whitespace char, if there is one.
+        let start = match rest.char_indices().rev().find(|(_, c)| c.is_whitespace()) {
+            Some((i, c)) => i + c.len_utf8(),
+            None => 0,
+        };
+        let word = &rest[start..];
+
+        let range = self.range_of(word);
+        self.back = range.start;
+        Some((range, word))
+    }
+}
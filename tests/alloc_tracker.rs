@@ -0,0 +1,41 @@
+use std::io::{self, Read};
+use std::process::Command;
+
+use rust_ownership::alloc_tracker::{self, Kind, Timeline, TrackingAllocator};
+use rust_ownership::lesson::{self, RUN_LESSON_ENV, TRACK_ENV};
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+fn kinds(timeline: &Timeline) -> Vec<(Kind, usize)> {
+    timeline.events.iter().map(|event| (event.kind, event.size)).collect()
+}
+
+#[test]
+fn a_string_is_freed_when_its_owner_goes_out_of_scope() {
+    let (_, lesson) = lesson::find("ownership::do_anything").unwrap();
+    let ((), timeline) = alloc_tracker::track(lesson.run.unwrap());
+
+    assert_eq!(kinds(&timeline), [(Kind::Alloc, 5), (Kind::Free, 5)]);
+    assert_eq!(timeline.live_bytes(), 0);
+}
+
+#[test]
+fn streams_the_allocations_in_order_with_the_prints() {
+    let (mut reader, writer) = io::pipe().unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rust-ownership"))
+        .env(RUN_LESSON_ENV, "ownership::main_function")
+        .env(TRACK_ENV, "1")
+        .stdout(writer.try_clone().unwrap())
+        .stderr(writer)
+        .spawn()
+        .unwrap();
+    let mut output = String::new();
+    reader.read_to_string(&mut output).unwrap();
+    assert!(child.wait().unwrap().success());
+
+    // takes_ownership prints the String before it frees it, makes_copy prints 5 after.
+    let timeline = Timeline::read(&output);
+    assert_eq!(kinds(&timeline), [(Kind::Alloc, 5), (Kind::Free, 5)]);
+    assert_eq!(timeline.prints, [(1, "hello".to_string()), (2, "5".to_string())]);
+}
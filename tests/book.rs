@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::Path;
+
+use rust_ownership::book::{self, Block};
+use rust_ownership::lesson::{self, CHAPTERS};
+
+#[test]
+fn every_lesson_is_under_its_section() {
+    for (chapter, lesson) in lesson::all() {
+        let headings: Vec<String> = book::sections(chapter).into_iter().filter_map(|section| section.heading).collect();
+        assert!(
+            lesson.section == chapter.title || headings.iter().any(|heading| heading == lesson.section),
+            "{} is under `{}`, which isn't a heading of the book",
+            chapter.id(lesson),
+            lesson.section
+        );
+    }
+}
+
+#[test]
+fn the_introduction_of_a_chapter_is_prose() {
+    let sections = book::sections(CHAPTERS[0]);
+    let intro = &sections[0];
+    assert_eq!(intro.heading, None);
+    assert!(matches!(&intro.blocks[0], Block::Prose(text) if text.starts_with("Ownership is Rust’s most unique feature")));
+    assert_eq!(sections[1].heading.as_deref(), Some("Ownership Rules"));
+
+    let page = book::to_markdown(CHAPTERS[0]);
+    assert!(page.starts_with("# What Is Ownership?\n"));
+    assert!(!page.contains("fn main() {\n    // Ownership"));
+    assert!(!page.contains("use crate::"));
+    assert!(!page.contains("pub const CHAPTER"));
+}
+
+#[test]
+fn examples_that_do_not_compile_replace_their_mod() {
+    let page = book::to_markdown(CHAPTERS[2]);
+    assert!(!page.contains("mod dsa;"));
+    assert!(!page.contains("#[cfg(feature"));
+    assert!(!page.contains("--features show-errors"));
+    assert!(!page.contains("//~"));
+    assert!(page.contains("```rust,compile_fail\nfn dsa () {"));
+    assert!(page.contains("error[E0502]: cannot borrow `s` as mutable because it is also borrowed as immutable\n```"));
+
+    let fails = book::sections(CHAPTERS[2]).into_iter().flat_map(|section| section.blocks).filter(|block| matches!(block, Block::Fails { .. }));
+    assert_eq!(fails.count(), 3);
+}
+
+#[test]
+fn writes_an_mdbook_with_a_table_of_contents() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("book");
+    let _ = fs::remove_dir_all(&dir);
+    let files = book::write(&dir).unwrap();
+    assert_eq!(files.len(), CHAPTERS.len() + 3);
+
+    let summary = fs::read_to_string(dir.join("src/SUMMARY.md")).unwrap();
+    for chapter in CHAPTERS.iter() {
+        assert!(summary.contains(&format!("[{}]({})", chapter.title, book::file_name(chapter))));
+        assert!(dir.join("src").join(book::file_name(chapter)).exists());
+    }
+
+    let contents = fs::read_to_string(dir.join("src/README.md")).unwrap();
+    assert!(contents.contains("[Ways Variables and Data Interact: Move](ownership.md#ways-variables-and-data-interact-move)"));
+    assert!(contents.contains("[Stack-Only Data: Copy](ownership.md#stack-only-data-copy)"));
+}
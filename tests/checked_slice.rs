@@ -0,0 +1,50 @@
+use rust_ownership::checked_slice::{ceil_char_boundary, checked_slice, floor_char_boundary, snapped_slice, SliceError};
+
+#[test]
+fn slices_like_indexing_when_the_range_is_valid() {
+    let s = "hello world";
+    assert_eq!(checked_slice(s, 0..5), Ok("hello"));
+    assert_eq!(checked_slice(s, 6..), Ok("world"));
+    assert_eq!(checked_slice(s, ..), Ok(s));
+    assert_eq!(checked_slice(s, 0..=4), Ok("hello"));
+    assert_eq!(checked_slice(s, 11..11), Ok(""));
+}
+
+#[test]
+fn reports_bounds_past_the_end() {
+    assert_eq!(checked_slice("hello", 3..6), Err(SliceError::OutOfBounds { index: 6, len: 5 }));
+    assert_eq!(checked_slice("hello", 7..), Err(SliceError::OutOfBounds { index: 7, len: 5 }));
+    assert_eq!(checked_slice("hello", ..=usize::MAX), Err(SliceError::OutOfBounds { index: usize::MAX, len: 5 }));
+}
+
+#[test]
+fn reports_inverted_ranges() {
+    #[allow(clippy::reversed_empty_ranges)]
+    let range = 4..2;
+    assert_eq!(checked_slice("hello", range), Err(SliceError::InvertedRange { start: 4, end: 2 }));
+}
+
+#[test]
+fn reports_bounds_inside_a_character() {
+    // Every letter takes two bytes.
+    let s = "Здравствуйте";
+    assert_eq!(checked_slice(s, 0..4), Ok("Зд"));
+    assert_eq!(checked_slice(s, 0..1), Err(SliceError::NotCharBoundary { index: 1 }));
+    assert_eq!(checked_slice(s, 3..), Err(SliceError::NotCharBoundary { index: 3 }));
+}
+
+#[test]
+fn snaps_to_whole_characters() {
+    let s = "Здравствуйте";
+    assert_eq!(snapped_slice(s, 1..3), "Зд");
+    assert_eq!(snapped_slice(s, 2..4), "д");
+    assert_eq!(snapped_slice(s, 20..100), "те");
+    assert_eq!(snapped_slice(s, 100..), "");
+    #[allow(clippy::reversed_empty_ranges)]
+    let range = 5..1;
+    assert_eq!(snapped_slice(s, range), "");
+
+    assert_eq!(floor_char_boundary("é!", 1), 0);
+    assert_eq!(ceil_char_boundary("é!", 1), 2);
+    assert_eq!(floor_char_boundary("é!", 9), 3);
+}
@@ -0,0 +1,7 @@
+fn error() {
+    let s1 = String::from("hello");
+    let s2 = s1;
+
+    println!("{}, world!", s1); //~ ERROR E0382: borrow of moved value: `s1`
+}
+// we get: error[E0382]: borrow of moved value: `s1`
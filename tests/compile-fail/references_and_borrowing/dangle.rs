@@ -0,0 +1,11 @@
+fn main_six() {
+    let reference_to_nothing = dangle();
+}
+
+// dangle returns a reference to a String
+fn dangle() -> &String { // error[E0106]: missing lifetime specifier, help: instead, you are more likely to want to return an owned value
+//~^ ERROR E0106: missing lifetime specifier
+    let s = String::from("hello"); // s is a new String
+
+    &s // we return a reference to the String, s
+} // Here, s goes out of scope, and is dropped. Its memory goes away. DANGER!
@@ -0,0 +1,11 @@
+fn dsa () {
+    let mut s = String::from("hello");
+
+    let r1 = &s; // no problem
+    let r2 = &s; // no problem
+    let r3 = &mut s; // BIG PROBLEM
+    //~^ ERROR E0502: cannot borrow `s` as mutable because it is also borrowed as immutable
+
+    println!("{}, {}, and {}", r1, r2, r3);
+}
+// error[E0502]: cannot borrow `s` as mutable because it is also borrowed as immutable
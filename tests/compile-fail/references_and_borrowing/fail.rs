@@ -0,0 +1,9 @@
+fn fail() {
+    let mut s = String::from("hello");
+
+    let r1 = &mut s;
+    let r2 = &mut s; // error[E0499]: cannot borrow `s` as mutable more than once at a time
+    //~^ ERROR E0499: cannot borrow `s` as mutable more than once at a time
+
+    println!("{}, {}", r1, r2);
+}
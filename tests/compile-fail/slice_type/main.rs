@@ -0,0 +1,24 @@
+fn main() {
+    let mut s = String::from("hello world");
+
+    let word = first_word(&s); // -- immutable borrow occurs here
+
+    s.clear(); // error! error[E0502]: cannot borrow `s` as mutable because it is also borrowed as immutable
+    // ^^^^^^^^^ mutable borrow occurs here
+    //~^^ ERROR E0502: cannot borrow `s` as mutable because it is also borrowed as immutable
+
+    println!("the first word is: {}", word); // ---- immutable borrow later used here
+}
+
+// first_word as defined in slice_type.rs
+fn first_word(s: &String) -> &str {
+    let bytes = s.as_bytes();
+
+    for (i, &item) in bytes.iter().enumerate() {
+        if item == b' ' {
+            return &s[0..i];
+        }
+    }
+
+    &s[..]
+}
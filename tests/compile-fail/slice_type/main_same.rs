@@ -0,0 +1,15 @@
+fn main_same() {
+    let mut mutable = String::from("Hello");
+
+    let immutable_reference = &mutable; // -------- immutable borrow occurs here
+
+    mutable.clear(); // error[E0502]: cannot borrow `mutable` as mutable because it is also borrowed as immutable
+    //~^ ERROR E0502: cannot borrow `mutable` as mutable because it is also borrowed as immutable
+    // here we try to modify the mutable, but we can't because we already have an immutable reference of the same variable
+    // the method clear() needs a mutable value, because it will truncate the String
+    // ^^^^^^^^^^^^^^^ mutable borrow occurs here
+
+    println!("{}", immutable_reference); // if this wasn't here, the code will be okay.
+                                        // But we need to use the immutable_reference again and the "main" reference was already "deleted"
+    // ------------------- immutable borrow later used here
+}
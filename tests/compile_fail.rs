@@ -0,0 +1,67 @@
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use rust_ownership::compile_fail::{self, Problem, EXAMPLES};
+
+#[test]
+fn examples_fail_with_the_error_codes_of_their_annotations() {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("compile-fail");
+    let mut failures = Vec::new();
+
+    for example in EXAMPLES {
+        let expected = example.expected_codes();
+        assert!(!expected.is_empty(), "{} has no `//~ ERROR` annotation", example.path);
+
+        let output = Command::new(&rustc)
+            .args(["--edition", "2018", "--crate-type", "lib", "--emit", "metadata", "--out-dir"])
+            .arg(&out_dir)
+            .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join(example.path))
+            .output()
+            .expect("failed to run rustc");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let actual = compile_fail::error_codes(&stderr);
+
+        if output.status.success() {
+            failures.push(format!("{} compiled, expected {:?}", example.path, expected));
+        } else if actual != expected {
+            failures.push(format!("{} failed with {:?}, expected {:?}\n{}", example.path, actual, expected, stderr));
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n\n"));
+}
+
+// The annotation is for the tests, the reader gets the error quoted in a comment of its own.
+#[test]
+fn examples_quote_the_errors_of_their_annotations() {
+    for example in EXAMPLES {
+        let quotes: Vec<&str> = example.source.lines().filter(|line| !line.trim_start().starts_with("//~")).collect();
+        for code in example.expected_codes() {
+            let quote = format!("error[{}]:", code);
+            assert!(quotes.iter().any(|line| line.contains(&quote)), "{} doesn't quote {} for the reader", example.path, code);
+        }
+    }
+}
+
+// Quoted error messages may go stale as rustc rewords them, `rust-ownership check` reports those.
+// The annotations are what must keep matching.
+#[test]
+fn examples_match_their_annotations() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("annotations");
+    let mut failures = Vec::new();
+
+    for example in EXAMPLES {
+        assert!(!compile_fail::annotations(example.source).is_empty(), "{} has no `//~ ERROR` annotation", example.path);
+
+        let report = example.check(&dir).expect("failed to run rustc");
+        let broken = report.problems.iter().any(|problem| !matches!(problem, Problem::StaleExplanation { .. }));
+        if broken {
+            failures.push(report.to_string());
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
@@ -0,0 +1,75 @@
+use rust_ownership::diagram::{self, Snapshot, SlotValue};
+use rust_ownership::lesson;
+
+#[test]
+fn diagrams_follow_the_lines_of_their_lesson() {
+    for diagram in diagram::DIAGRAMS {
+        let snapshots = diagram.snapshots().unwrap();
+        let source = lesson::function_source(diagram.lesson).unwrap();
+        assert!(!snapshots.is_empty(), "{}", diagram.lesson);
+        for snapshot in &snapshots {
+            assert!(source.contains(&snapshot.code), "{} has no line `{}`", diagram.lesson, snapshot.code);
+        }
+    }
+}
+
+#[test]
+fn slices_point_into_the_buffer_of_their_string() {
+    let snapshots = diagram::find("slice_type::a").unwrap().snapshots().unwrap();
+    let last = snapshots.last().unwrap();
+    assert_eq!(last.code, "let world = &s[6..11];");
+
+    let (s, world) = (&last.frames[0].slots[0], &last.frames[0].slots[2]);
+    let (SlotValue::String { ptr: start, .. }, SlotValue::Str { ptr, len }) = (&s.value, &world.value) else {
+        panic!("{:?}", last.frames)
+    };
+    assert_eq!((ptr - start, *len), (6, 5));
+    assert_eq!(last.buffer_of(*ptr), Some((0, 6)));
+}
+
+// The drawings of a step, compared with the files in tests/snapshots named after the lesson and the step.
+#[test]
+fn records_the_real_pointer_length_and_capacity_of_a_string() {
+    let mut s = String::with_capacity(10);
+    s.push_str("hello");
+    let snapshot = Snapshot::new("s.push_str(\"hello\");").string("s", &s).str("he", &s[..2]);
+
+    let ptr = s.as_ptr() as usize;
+    assert_eq!(snapshot.frames[0].slots[0].value, SlotValue::String { ptr, len: 5, capacity: s.capacity() });
+    assert_eq!(snapshot.frames[0].slots[1].value, SlotValue::Str { ptr, len: 2 });
+    assert_eq!((snapshot.heap[0].ptr, snapshot.heap[0].capacity, &snapshot.heap[0].bytes[..]), (ptr, 10, &b"hello"[..]));
+}
+
+// The live diagrams have the lines of the simulated ones, with the addresses of the run.
+#[test]
+fn live_diagrams_show_the_same_steps_with_real_addresses() {
+    for diagram in diagram::DIAGRAMS {
+        let codes = |snapshots: Vec<Snapshot>| snapshots.into_iter().map(|snapshot| snapshot.code).collect::<Vec<_>>();
+        assert_eq!(codes((diagram.live)()), codes(diagram.snapshots().unwrap()), "{}", diagram.lesson);
+    }
+
+    let string = |snapshot: &Snapshot, slot: usize| match snapshot.frames[0].slots[slot].value {
+        SlotValue::String { ptr, .. } => ptr,
+        ref value => panic!("{:?} is not a String", value),
+    };
+    let bye = (diagram::find("ownership::bye").unwrap().live)();
+    assert_eq!(string(&bye[1], 1), string(&bye[0], 0), "the move keeps the buffer");
+    let cloning = (diagram::find("ownership::cloning").unwrap().live)();
+    assert_ne!(string(&cloning[1], 1), string(&cloning[1], 0), "the clone has a buffer of its own");
+}
+
+fn snapshot(lesson: &str, step: usize) -> diagram::Snapshot {
+    diagram::find(lesson).unwrap().snapshots().unwrap().remove(step - 1)
+}
+
+#[test]
+fn draws_the_stack_and_the_heap_as_text() {
+    assert_eq!(snapshot("ownership::bye", 2).to_string(), include_str!("snapshots/ownership-bye-2.txt"));
+    assert_eq!(snapshot("slice_type::a", 3).to_string(), include_str!("snapshots/slice_type-a-3.txt"));
+}
+
+#[test]
+fn draws_the_stack_and_the_heap_as_svg_and_dot() {
+    assert_eq!(snapshot("slice_type::a", 3).to_svg("slice_type-a-3"), include_str!("snapshots/slice_type-a-3.svg"));
+    assert_eq!(snapshot("ownership::cloning", 2).to_dot(), include_str!("snapshots/ownership-cloning-2.dot"));
+}
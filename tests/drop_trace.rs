@@ -0,0 +1,34 @@
+use std::process::Command;
+
+use rust_ownership::drop_trace::EventLog;
+use rust_ownership::lesson::RUN_LESSON_ENV;
+
+// The variables a traced lesson says go out of scope, in the order it says it.
+fn out_of_scope(id: &str) -> Vec<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-ownership")).env(RUN_LESSON_ENV, id).output().unwrap();
+    assert!(output.status.success(), "{} failed", id);
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("[trace] "))
+        .filter_map(|event| event.split_once(" goes out of scope"))
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+#[test]
+fn parameters_are_dropped_with_their_function_and_variables_in_reverse_order() {
+    assert_eq!(out_of_scope("ownership::main_function_traced"), ["some_string", "some_integer", "x", "s"]);
+    assert_eq!(out_of_scope("ownership::another_main_function_traced"), ["s3", "s2", "s1"]);
+}
+
+#[test]
+fn tells_where_each_binding_is_made() {
+    let log = EventLog::new();
+    let line = line!() + 1;
+    let s = log.traced("s", String::from("hello"));
+    let _t = s.moved_to("t");
+
+    let at = format!("at {}:{}", file!(), line);
+    let moved_at = format!("at {}:{}", file!(), line + 1);
+    assert_eq!(log.events()[..2], [format!("s comes into scope {}", at), format!("s moved into t {}", moved_at)]);
+}
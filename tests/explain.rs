@@ -0,0 +1,79 @@
+use std::path::{Path, PathBuf};
+
+use rust_ownership::compile_fail;
+use rust_ownership::explain::{self, Explanation};
+use rust_ownership::lesson::{self, CHAPTERS};
+
+fn dir() -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join("explain")
+}
+
+fn explain(code: &str) -> Option<Explanation> {
+    explain::explain(code, &dir()).expect("failed to run rustc")
+}
+
+#[test]
+fn every_error_of_the_chapters_is_explained_and_fixed() {
+    let mut codes: Vec<&str> = CHAPTERS.iter().flat_map(|chapter| compile_fail::error_codes(chapter.source)).collect();
+    codes.extend(explain::codes());
+    for code in ["E0382", "E0499", "E0502", "E0106", "E0596"] {
+        assert!(codes.contains(&code), "nothing fails with {} anymore", code);
+    }
+
+    for code in codes {
+        let explanation = explain(code).unwrap_or_else(|| panic!("{} isn't explained", code));
+        assert!(!explanation.fails.is_empty(), "no example fails with {}", code);
+        assert!(!explanation.fixed.is_empty(), "nothing fixes {}", code);
+
+        for passage in &explanation.fails {
+            assert!(passage.errors.iter().any(|error| error.code.as_deref() == Some(code)), "{} doesn't fail with {}", passage.lesson, code);
+        }
+        for passage in &explanation.fixed {
+            let (_, lesson) = lesson::find(&passage.lesson).unwrap();
+            assert!(lesson.run.is_some(), "{} fixes {} but doesn't compile", passage.lesson, code);
+        }
+    }
+}
+
+#[test]
+fn explains_e0502_with_dsa_and_the_slices_and_fixes_them_with_ddas_and_main_fixed() {
+    let explanation = explain("e0502").unwrap();
+    assert_eq!(explanation.code, "E0502");
+
+    let fails: Vec<&str> = explanation.fails.iter().map(|passage| passage.lesson.as_str()).collect();
+    assert_eq!(fails, ["references_and_borrowing::dsa", "slice_type::main", "slice_type::main_same"]);
+    let fixed: Vec<&str> = explanation.fixed.iter().map(|passage| passage.lesson.as_str()).collect();
+    assert_eq!(fixed, ["references_and_borrowing::ddas", "slice_type::main_fixed"]);
+
+    let dsa = &explanation.fails[0];
+    assert_eq!(dsa.before.as_deref(), Some("A similar rule exists for combining mutable and immutable references."));
+    assert!(dsa.after[0].starts_with("Whew! We also cannot have a mutable reference while we have an immutable one."));
+    // The next paragraph introduces ddas.
+    assert_eq!(dsa.after.len(), 1);
+
+    let text = explanation.to_string();
+    let marked = text.lines().position(|line| line == "        let r3 = &mut s; // BIG PROBLEM").unwrap();
+    let caret = text.lines().nth(marked + 1).unwrap();
+    assert_eq!(caret, "                 ^ error[E0502]: cannot borrow `s` as mutable because it is also borrowed as immutable");
+    // Once per example: the errors the comments quote give way to the carets.
+    assert_eq!(text.matches("error[E0502]").count(), 3);
+
+    assert!(explain("0502").is_some());
+    assert!(explain("E0001").is_none());
+}
+
+#[test]
+fn puts_back_the_line_a_chapter_comments_out_to_show_its_error() {
+    let explanation = explain("E0596").unwrap();
+    assert_eq!(explanation.fails[0].lesson, "references_and_borrowing::main_four");
+    assert!(explanation.fails[0].put_back);
+
+    let text = explanation.to_string();
+    assert!(text.starts_with("E0596: a value is changed through a `&` reference\n"));
+    let lines: Vec<&str> = text.lines().collect();
+    let marked = lines.iter().position(|line| *line == "        some_string.push_str(\", world\");").unwrap();
+    assert_eq!(lines[marked + 1], "        ^ error[E0596]: cannot borrow `*some_string` as mutable, as it is behind a `&` reference");
+    assert!(!text.contains("//~"));
+    assert!(text.contains("== this does: references_and_borrowing::main_five"));
+    assert!(text.contains("We can fix the previous error in the code with just a small tweak:"));
+}
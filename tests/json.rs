@@ -0,0 +1,32 @@
+use rust_ownership::json::{self, Value};
+
+#[test]
+fn round_trips_what_it_writes() {
+    let value = Value::Object(vec![
+        ("lesson".to_string(), "slice_type::a".into()),
+        ("line".to_string(), 48usize.into()),
+        ("compiles".to_string(), true.into()),
+        ("error".to_string(), Value::Null),
+        ("output".to_string(), Value::Array(vec!["tab\t, quote \" and \u{1} \u{e9}".into(), Value::Number(-0.5)])),
+    ]);
+    let text = value.to_string();
+    assert_eq!(text, r#"{"lesson":"slice_type::a","line":48,"compiles":true,"error":null,"output":["tab\t, quote \" and \u0001 é",-0.5]}"#);
+    assert_eq!(json::parse(&text), Ok(value));
+    assert_eq!(json::parse(" \r\n\t[1, \"\\u00e9\\ud83e\\udd80\"]\n"), Ok(Value::Array(vec![Value::Number(1.0), "é🦀".into()])));
+}
+
+#[test]
+fn rejects_what_json_does_not_allow() {
+    // A no-break space is whitespace to `trim_start`, not to JSON.
+    assert_eq!(json::parse("\u{a0}1").unwrap_err().message, "unexpected character");
+    assert_eq!(json::parse("1\u{2003}").unwrap_err().message, "trailing characters");
+    assert_eq!(json::parse(r#""\u+123""#).unwrap_err().message, "invalid unicode escape");
+    assert_eq!(json::parse("1e400").unwrap_err().message, "number out of range");
+}
+
+#[test]
+fn writes_infinity_and_nan_as_null() {
+    let numbers = Value::Array(vec![Value::Number(f64::INFINITY), Value::Number(f64::NAN), Value::Number(1.5)]);
+    assert_eq!(numbers.to_string(), "[null,null,1.5]");
+    assert!(json::parse(&numbers.to_string()).is_ok());
+}
@@ -0,0 +1,43 @@
+use rust_ownership::compile_fail;
+use rust_ownership::lesson::{self, Chapter};
+
+#[test]
+fn every_lesson_is_a_function_of_its_chapter() {
+    for (chapter, lesson) in lesson::all() {
+        let id = chapter.id(lesson);
+        assert!(lesson::find(&id).is_some(), "can't find {}", id);
+        assert!(chapter.source_of(lesson).is_some(), "no source for {}", id);
+        if compile_fail::find(&id).is_some() {
+            continue;
+        }
+
+        let lines: Vec<&str> = chapter.source.lines().collect();
+        let (start, end) = lesson::function_lines(&id).unwrap();
+        assert!(lesson::is_signature_of(lines[start - 1], lesson.name), "{} starts on line {}", id, start);
+        assert_eq!(lesson::split_comment(lines[end - 1]).0.trim_end(), "}", "{} ends on line {}", id, end);
+    }
+}
+
+#[test]
+fn a_function_ends_at_the_brace_that_closes_it() {
+    let source = r#"fn nested() {
+    for c in "a { b".chars() {
+        if c == '{' {
+            println!("}} {}", c); // a } here is a comment
+        }
+    }
+}
+
+fn next() {}
+"#;
+    let chapter = Chapter { module: "braces", source, title: "Braces", lessons: &[] };
+    assert_eq!(chapter.function_lines("nested"), Some((1, 7)));
+    assert_eq!(chapter.function_lines("next"), Some((9, 9)));
+}
+
+#[test]
+fn the_source_of_an_example_has_no_annotations() {
+    let source = lesson::function_source("references_and_borrowing::dsa").unwrap();
+    assert!(source.contains("let r3 = &mut s;"));
+    assert!(!source.contains("//~"), "{}", source);
+}
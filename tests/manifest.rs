@@ -0,0 +1,79 @@
+use std::io;
+use std::process::Command;
+
+use rust_ownership::json;
+use rust_ownership::lesson::{self, RUN_LESSON_ENV};
+use rust_ownership::manifest::{self, Entry};
+
+// What the binary prints for a lesson, the way `rust-ownership manifest` gets it.
+fn run(id: &str) -> io::Result<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-ownership")).env(RUN_LESSON_ENV, id).output()?;
+    assert!(output.status.success(), "{} failed", id);
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn entries() -> Vec<Entry> {
+    manifest::entries(|_| Ok("(not run)".to_string())).unwrap()
+}
+
+fn entry<'a>(entries: &'a [Entry], id: &str) -> &'a Entry {
+    entries.iter().find(|entry| entry.id == id).unwrap_or_else(|| panic!("no entry for {}", id))
+}
+
+#[test]
+fn lists_every_lesson_once() {
+    let entries = entries();
+    let mut lessons: Vec<String> = entries.iter().filter(|entry| entry.title.is_some()).map(|entry| entry.id.clone()).collect();
+    let mut expected: Vec<String> = lesson::all().map(|(chapter, lesson)| chapter.id(lesson)).collect();
+    lessons.sort();
+    expected.sort();
+    assert_eq!(lessons, expected);
+}
+
+#[test]
+fn describes_functions_that_are_not_lessons() {
+    let entries = entries();
+    let change_two = entry(&entries, "references_and_borrowing::change_two");
+    assert_eq!(change_two.section, "Mutable References");
+    assert_eq!(change_two.title, None);
+    assert_eq!(change_two.stdout, None);
+    assert!(change_two.compiles);
+
+    let source = include_str!("../src/references_and_borrowing.rs");
+    let lines: Vec<&str> = source.lines().collect();
+    let (start, end) = change_two.lines;
+    assert_eq!(lines[start - 1], "fn change_two(some_string: &mut String) {");
+    assert_eq!(lines[end - 1], "}");
+}
+
+#[test]
+fn describes_examples_that_do_not_compile() {
+    let entries = entries();
+    let dsa = entry(&entries, "references_and_borrowing::dsa");
+    assert!(!dsa.compiles);
+    assert_eq!(dsa.file, "tests/compile-fail/references_and_borrowing/dsa.rs");
+    assert_eq!(dsa.stdout, None);
+    let (code, message) = dsa.expected_error.as_ref().unwrap();
+    assert_eq!(code, "E0502");
+    assert!(message.starts_with("cannot borrow `s` as mutable"));
+}
+
+#[test]
+fn records_what_the_lessons_print() {
+    let entries = manifest::entries(|id| if id.starts_with("slice_type::") { run(id) } else { Ok(String::new()) }).unwrap();
+    assert_eq!(entry(&entries, "slice_type::first_word").stdout.as_deref(), Some("hello\n"));
+    assert!(entry(&entries, "slice_type::main_index").stdout.as_deref().unwrap().contains("the first word is: goodb\n"));
+
+    let value = json::parse(&manifest::to_json(&entries).to_string()).unwrap();
+    assert_eq!(value.get("version").and_then(|version| version.as_u64()), Some(manifest::VERSION as u64));
+    let first_word = value
+        .get("entries")
+        .and_then(|entries| entries.as_array())
+        .and_then(|entries| entries.iter().find(|entry| entry.get("id").and_then(|id| id.as_str()) == Some("slice_type::first_word")))
+        .unwrap();
+    assert_eq!(first_word.get("function").and_then(|function| function.as_str()), Some("first_word"));
+    assert_eq!(first_word.get("stdout").and_then(|stdout| stdout.as_str()), Some("hello\n"));
+    let source = include_str!("../src/slice_type.rs");
+    let start = source.lines().position(|line| line == "fn first_word(s: &String) -> &str {").unwrap() + 1;
+    assert_eq!(first_word.get("lines").and_then(|lines| lines.get("start")).and_then(|start| start.as_u64()), Some(start as u64));
+}
@@ -0,0 +1,117 @@
+use std::fs;
+use std::io::{BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+
+use rust_ownership::json;
+use rust_ownership::lesson::{self, RUN_LESSON_ENV};
+use rust_ownership::playground::{self, Request, RUN_HEADER};
+use rust_ownership::rustc;
+
+fn dir() -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join("playground")
+}
+
+fn request(text: &str) -> Request {
+    Request::read(&mut BufReader::new(text.as_bytes())).unwrap()
+}
+
+#[test]
+fn every_lesson_runs_in_the_playground_as_it_runs_in_the_binary() {
+    for (chapter, lesson) in lesson::all() {
+        let id = chapter.id(lesson);
+        let program = match playground::program(&id) {
+            Some(program) => program,
+            None => {
+                assert!(id.ends_with("_traced") || id == "slice_type::checked", "{} can't run in the playground", id);
+                continue;
+            }
+        };
+        assert!(!program.contains("//~"));
+
+        let run = rustc::run(&program, "lesson", &dir().join(id.replace("::", "_")), playground::TIMEOUT).unwrap();
+        let output = match (run.output, lesson.run) {
+            (Some(output), Some(_)) => output,
+            (None, None) => continue,
+            (output, _) => panic!("{} built: {}, but it is a lesson that compiles: {}\n{}", id, output.is_some(), lesson.run.is_some(), program),
+        };
+
+        let binary = Command::new(env!("CARGO_BIN_EXE_rust-ownership")).env(RUN_LESSON_ENV, &id).output().unwrap();
+        assert_eq!(output.stdout, String::from_utf8_lossy(&binary.stdout), "{} prints something else", id);
+    }
+}
+
+#[test]
+fn preloads_the_functions_a_lesson_calls() {
+    let program = playground::program("references_and_borrowing::main_five").unwrap();
+    assert!(program.contains("fn change_two(some_string: &mut String)"));
+    assert!(program.ends_with("fn main() {\n    main_five();\n}\n"));
+
+    let program = playground::program("slice_type::first_word").unwrap();
+    assert!(program.ends_with("fn main() {\n    println!(\"{}\", first_word(&String::from(\"hello world\")));\n}\n"));
+}
+
+#[test]
+fn reads_requests() {
+    let request = request("POST /run?lesson=slice_type%3A%3Aa&x=a+b HTTP/1.1\r\nHost: localhost:7878\r\nContent-Length: 5\r\n\r\nhello");
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/run");
+    assert_eq!(request.param("lesson"), Some("slice_type::a"));
+    assert_eq!(request.param("x"), Some("a b"));
+    assert_eq!(request.header("host"), Some("localhost:7878"));
+    assert_eq!(request.body, "hello");
+}
+
+#[test]
+fn only_runs_code_asked_for_by_its_own_page() {
+    let run = "POST /run HTTP/1.1\r\nHost: 127.0.0.1:7878\r\nContent-Length: 12\r\n\r\nfn main() {}";
+    assert_eq!(playground::handle(&request(run), &dir()).status, 403);
+
+    let elsewhere = format!("POST /run HTTP/1.1\r\nHost: attacker.example\r\n{}: run\r\nContent-Length: 12\r\n\r\nfn main() {{}}", RUN_HEADER);
+    assert_eq!(playground::handle(&request(&elsewhere), &dir()).status, 403);
+
+    let page = playground::handle(&request("GET /?lesson=references_and_borrowing::dsa HTTP/1.1\r\nHost: localhost:7878\r\n\r\n"), &dir());
+    assert_eq!(page.status, 200);
+    assert!(page.body.contains("let r3 = &amp;mut s;"));
+    assert!(page.body.contains("<option value=\"references_and_borrowing::dsa\" selected>"));
+
+    let missing = playground::handle(&request("GET /?lesson=nothing::here HTTP/1.1\r\nHost: localhost\r\n\r\n"), &dir());
+    assert_eq!(missing.status, 404);
+}
+
+#[test]
+fn answers_with_diagnostics_and_output() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let served = dir().join("served");
+    let _ = fs::remove_dir_all(&served);
+    fs::create_dir_all(&served).unwrap();
+    let base = served.clone();
+    thread::spawn(move || playground::serve(listener, base));
+
+    let post = |code: &str| {
+        let mut stream = TcpStream::connect(address).unwrap();
+        let request = format!("POST /run HTTP/1.1\r\nHost: {}\r\n{}: run\r\nContent-Length: {}\r\n\r\n{}", address, RUN_HEADER, code.len(), code);
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        json::parse(response.split_once("\r\n\r\n").unwrap().1.trim()).unwrap()
+    };
+
+    let ran = post("fn main() {\n    println!(\"hello\");\n}\n");
+    assert_eq!(ran.get("success").and_then(|success| success.as_bool()), Some(true));
+    assert_eq!(ran.get("stdout").and_then(|stdout| stdout.as_str()), Some("hello\n"));
+
+    let failed = post("fn main() {\n    let s1 = String::from(\"hello\");\n    let s2 = s1;\n    println!(\"{}\", s1);\n}\n");
+    assert_eq!(failed.get("built").and_then(|built| built.as_bool()), Some(false));
+    let error = &failed.get("diagnostics").and_then(|diagnostics| diagnostics.as_array()).unwrap()[0];
+    assert_eq!(error.get("code").and_then(|code| code.as_str()), Some("E0382"));
+    assert_eq!(error.get("line").and_then(|line| line.as_u64()), Some(4));
+    assert!(error.get("rendered").and_then(|rendered| rendered.as_str()).unwrap().contains("--> playground.rs:4:20"));
+
+    // Each request builds in a directory of its own, gone by the time it is answered.
+    assert_eq!(fs::read_dir(&served).unwrap().count(), 0);
+}
@@ -0,0 +1,86 @@
+use std::path::Path;
+use std::process::Command;
+
+use rust_ownership::profile::{Profile, HOME_ENV, USER_ENV};
+use rust_ownership::progress::{self, Progress, Step};
+use rust_ownership::{json, lesson};
+
+fn next_id(progress: &Progress) -> Option<String> {
+    progress.next().map(|(chapter, lesson)| chapter.id(lesson))
+}
+
+fn finish(progress: &mut Progress, id: &str) {
+    progress.record(id, Step::Viewed);
+    progress.record(id, Step::Run);
+}
+
+#[test]
+fn starts_at_the_first_lesson() {
+    let (chapter, lesson) = lesson::all().next().unwrap();
+    assert_eq!(next_id(&Progress::default()), Some(chapter.id(lesson)));
+}
+
+#[test]
+fn resumes_after_the_last_lesson_looked_at() {
+    let mut progress = Progress::default();
+    finish(&mut progress, "references_and_borrowing::main_three");
+    assert_eq!(next_id(&progress).as_deref(), Some("references_and_borrowing::main_four"));
+
+    // Viewing a lesson without running it makes it the last one, but it isn't done yet.
+    progress.record("slice_type::a", Step::Viewed);
+    assert_eq!(next_id(&progress).as_deref(), Some("slice_type::b"));
+    assert!(!progress.of("slice_type::a").is_done());
+
+    // Passing in the quiz is not looking at a lesson.
+    progress.record("slice_type::b", Step::Passed);
+    assert_eq!(progress.last.as_deref(), Some("slice_type::a"));
+}
+
+#[test]
+fn goes_back_to_the_lessons_skipped() {
+    let mut progress = Progress::default();
+    let ids: Vec<String> = lesson::all().map(|(chapter, lesson)| chapter.id(lesson)).collect();
+    for id in &ids[1..] {
+        finish(&mut progress, id);
+    }
+    assert_eq!(next_id(&progress).as_ref(), Some(&ids[0]));
+
+    finish(&mut progress, &ids[0]);
+    assert_eq!(next_id(&progress), None);
+}
+
+// Saving and loading a profile file is tested with the quiz results, only the JSON of the progress is its own.
+#[test]
+fn round_trips_through_json() {
+    assert_eq!(Progress::from_json(&json::Value::Null), Progress::default());
+
+    let mut progress = Progress::default();
+    finish(&mut progress, "ownership::bye");
+    progress.record("ownership::error", Step::Passed);
+
+    let loaded = Progress::from_json(&json::parse(&progress.to_json().to_string()).unwrap());
+    assert_eq!(loaded, progress);
+    assert!(loaded.of("ownership::bye").is_done());
+    assert!(loaded.of("ownership::error").passed);
+    assert_eq!(loaded.last.as_deref(), Some("ownership::bye"));
+}
+
+// `rust-ownership run`, with the progress kept in a profile of its own.
+fn run(profile: &Profile, args: &[&str]) {
+    let base = profile.dir.parent().unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-ownership")).args(args).env(HOME_ENV, base).env(USER_ENV, &profile.user).output();
+    assert!(output.unwrap().status.success(), "{:?} failed", args);
+}
+
+#[test]
+fn only_a_lesson_that_ran_counts_as_run() {
+    let profile = Profile::new("runner", Path::new(env!("CARGO_TARGET_TMPDIR")).join("progress-run"));
+    let _ = std::fs::remove_dir_all(&profile.dir);
+
+    run(&profile, &["run", "references_and_borrowing::dsa"]);
+    run(&profile, &["run", "ownership::bye"]);
+
+    let progress = Progress::from_json(&profile.load(progress::PROGRESS_FILE).unwrap());
+    assert!(!progress.of("references_and_borrowing::dsa").run, "an example that doesn't compile can't run");
+    assert!(progress.of("ownership::bye").run);
+}
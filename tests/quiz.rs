@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use rust_ownership::compile_fail::EXAMPLES;
+use rust_ownership::profile::Profile;
+use rust_ownership::quiz::{self, Question, Results, TASKS, TOPICS};
+
+#[test]
+fn every_example_that_does_not_compile_has_its_error_among_the_choices() {
+    for example in EXAMPLES {
+        let question = Question::about(example.lesson).unwrap_or_else(|| panic!("no question about {}", example.lesson));
+        assert_ne!(question.answer, 0, "{} doesn't compile", example.lesson);
+    }
+}
+
+#[test]
+fn asks_whether_dsa_and_ddas_compile() {
+    let dsa = Question::about("references_and_borrowing::dsa").unwrap();
+    assert!(dsa.choices[dsa.answer].contains("E0502"));
+    assert_eq!(dsa.grade("1"), Some(false));
+    assert_eq!(dsa.grade(&(dsa.answer + 1).to_string()), Some(true));
+    assert!(!dsa.code.contains("error"), "the comments give the answer away:\n{}", dsa.code);
+
+    let ddas = Question::about("references_and_borrowing::ddas").unwrap();
+    assert_eq!(ddas.answer, 0);
+    assert_eq!(ddas.grade("1"), Some(true));
+    assert_eq!(ddas.grade("9"), None);
+    assert_eq!(ddas.grade("yes"), None);
+}
+
+#[test]
+fn every_topic_has_questions_and_a_task() {
+    for topic in TOPICS {
+        assert!(!topic.questions().is_empty(), "{} has no questions", topic.name);
+        assert!(topic.tasks().next().is_some(), "{} has no task", topic.name);
+    }
+    assert_eq!(quiz::topic("mutable-references").map(|topic| topic.name), Some("Mutable References"));
+}
+
+#[test]
+fn rustc_grades_the_tasks() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("quiz");
+
+    for task in TASKS {
+        let grade = task.grade(task.solution, &dir).expect("failed to run rustc");
+        let errors: Vec<&str> = grade.errors.iter().map(|error| error.rendered.as_str()).collect();
+        assert!(grade.passed, "the solution of {} doesn't compile:\n{}", task.id, errors.join("\n"));
+
+        assert!(!task.grade("", &dir).unwrap().passed);
+    }
+
+    let clone = quiz::task("clone").unwrap();
+    let grade = clone.grade("s1", &dir).unwrap();
+    assert!(!grade.passed);
+    assert_eq!(grade.errors[0].code.as_deref(), Some("E0382"));
+}
+
+#[test]
+fn answers_that_compile_but_print_the_wrong_thing_fail() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("quiz-output");
+
+    let first_word = quiz::task("first_word").unwrap();
+    for answer in ["s", "\"\""] {
+        let grade = first_word.grade(answer, &dir).unwrap();
+        assert!(!grade.passed, "{} passes", answer);
+        assert!(grade.errors.is_empty());
+    }
+    assert_eq!(first_word.grade("s", &dir).unwrap().printed.as_deref(), Some("hello world\n"));
+
+    let grade = quiz::task("clone").unwrap().grade("String::new()", &dir).unwrap();
+    assert!(!grade.passed);
+    assert_eq!(grade.printed.as_deref(), Some("s1 = hello, s2 = \n"));
+}
+
+#[test]
+fn keeps_results_in_the_profile() {
+    let profile = Profile::new("trainee", Path::new(env!("CARGO_TARGET_TMPDIR")).join("profiles"));
+    let _ = std::fs::remove_dir_all(&profile.dir);
+    assert_eq!(Results::from_json(&profile.load(quiz::RESULTS_FILE).unwrap()), Results::default());
+
+    let mut results = Results::default();
+    results.record("task:clone", false);
+    results.record("task:clone", true);
+    results.record("compiles:references_and_borrowing::dsa", false);
+    profile.save(quiz::RESULTS_FILE, &results.to_json()).unwrap();
+
+    let loaded = Results::from_json(&profile.load(quiz::RESULTS_FILE).unwrap());
+    assert_eq!(loaded, results);
+    assert!(loaded.passed("task:clone"));
+    assert!(!loaded.passed("compiles:references_and_borrowing::dsa"));
+    assert_eq!(loaded.exercises[0].tries, 2);
+}
@@ -0,0 +1,56 @@
+use rust_ownership::repl::Session;
+
+fn enter(session: &mut Session, line: &str) -> String {
+    session.enter(line).expect("only :quit ends the session")
+}
+
+#[test]
+fn tells_what_each_statement_does() {
+    let mut session = Session::new();
+    assert!(enter(&mut session, r#"let s1 = String::from("hi")"#).contains("s1 owns a new String \"hi\""));
+    assert!(enter(&mut session, "let s2 = s1").contains("s1 moved into s2; s1 invalid"));
+    assert!(enter(&mut session, "print s2").contains("println! prints \"hi\""));
+}
+
+#[test]
+fn rolls_back_a_statement_that_breaks_a_rule() {
+    let mut session = Session::new();
+    enter(&mut session, r#"let s1 = String::from("hi")"#);
+    enter(&mut session, "let s2 = s1");
+
+    let told = enter(&mut session, "print s1");
+    assert!(told.contains("error[E0382]: borrow of moved value: `s1`"));
+    assert!(told.contains("rolled back"));
+    assert!(!enter(&mut session, ":list").contains("println!"));
+}
+
+#[test]
+fn waits_for_the_end_of_a_block() {
+    let mut session = Session::new();
+    enter(&mut session, r#"let mut s = String::from("hi")"#);
+    assert_eq!(enter(&mut session, "{"), "");
+    assert_eq!(session.prompt(), ".. ");
+    enter(&mut session, "    let r1 = &mut s;");
+    assert!(enter(&mut session, "}").contains("r1 borrows s mutably"));
+    assert!(enter(&mut session, "let r2 = &mut s").contains("r2 borrows s mutably"));
+}
+
+#[test]
+fn loads_scenarios_up_to_their_error() {
+    let mut session = Session::new();
+    let told = enter(&mut session, ":load dsa");
+    assert!(told.contains("error[E0502]"));
+    assert!(told.contains("the rest of the scenario was not loaded"));
+    assert!(enter(&mut session, ":list").contains("let r3 = &mut s;"));
+
+    let told = enter(&mut session, ":load bye");
+    assert!(told.contains("s1 moved into s2"));
+    let diagram = enter(&mut session, ":diagram");
+    assert!(diagram.contains("moved into s2, no longer valid"));
+    assert!(diagram.contains("| h | e | l | l | o |"));
+}
+
+#[test]
+fn quits() {
+    assert_eq!(Session::new().enter(":quit"), None);
+}
@@ -0,0 +1,130 @@
+use rust_ownership::simulator::{self, Diagnostic, Liveness, Rule, Trace};
+
+fn scenario(name: &str, liveness: Liveness) -> Trace {
+    let source = simulator::scenario(name).unwrap_or_else(|| panic!("no scenario {}", name)).source();
+    simulator::simulate_with(&source, liveness).unwrap()
+}
+
+fn error(trace: &Trace) -> &Diagnostic {
+    trace.error.as_ref().unwrap_or_else(|| panic!("no error in:\n{}", trace))
+}
+
+fn told(trace: &Trace, text: &str) -> bool {
+    trace.events.iter().any(|event| event.text.contains(text))
+}
+
+#[test]
+fn the_lessons_that_compile_have_no_error() {
+    for name in ["bye", "asd", "ddas"] {
+        let trace = scenario(name, Liveness::NonLexical);
+        assert_eq!(trace.error, None, "{}:\n{}", name, trace);
+    }
+    assert!(told(&scenario("bye", Liveness::NonLexical), "s1 moved into s2; s1 invalid"));
+    assert!(told(&scenario("ddas", Liveness::NonLexical), "r2 is not used after this line, so its borrow of s ends here"));
+}
+
+#[test]
+fn using_a_moved_value_is_e0382() {
+    let trace = scenario("error", Liveness::NonLexical);
+    let error = error(&trace);
+    assert_eq!((error.code, error.line), ("E0382", 5));
+    assert_eq!(error.message, "borrow of moved value: `s1`");
+    assert_eq!(error.rule, None);
+}
+
+#[test]
+fn two_mutable_borrows_are_e0499() {
+    let trace = scenario("fail", Liveness::NonLexical);
+    let error = error(&trace);
+    assert_eq!((error.code, error.line), ("E0499", 5));
+    assert_eq!(error.rule, Some(Rule::OneMutableOrManyShared));
+}
+
+#[test]
+fn a_mutable_borrow_while_shared_ones_are_used_is_e0502() {
+    let trace = scenario("dsa", Liveness::NonLexical);
+    let error = error(&trace);
+    assert_eq!((error.code, error.line), ("E0502", 6));
+    assert_eq!(error.message, "cannot borrow `s` as mutable because it is also borrowed as immutable");
+    assert_eq!(error.rule, Some(Rule::OneMutableOrManyShared));
+}
+
+#[test]
+fn ddas_only_compiles_with_non_lexical_lifetimes() {
+    let trace = scenario("ddas", Liveness::Lexical);
+    let error = error(&trace);
+    assert_eq!(error.code, "E0502");
+    assert_eq!(error.rule, Some(Rule::OneMutableOrManyShared));
+    assert!(error.notes.iter().any(|note| note.contains("with lexical lifetimes a borrow lasts until the end of the scope")));
+}
+
+#[test]
+fn returning_a_reference_to_nothing_is_e0106() {
+    let trace = scenario("dangle", Liveness::NonLexical);
+    let error = error(&trace);
+    assert_eq!(error.code, "E0106");
+    assert_eq!(error.message, "missing lifetime specifier");
+    assert_eq!(error.rule, Some(Rule::AlwaysValid));
+}
+
+#[test]
+fn assigning_to_a_borrowed_value_is_e0506_and_allocates_nothing() {
+    let trace = simulator::simulate("let mut s = String::from(\"x\");\nlet r = &s;\ns = String::from(\"y\");\nprintln!(\"{}\", r);\n").unwrap();
+    let error = error(&trace);
+    assert_eq!((error.code, error.line), ("E0506", 3));
+    assert_eq!(error.rule, Some(Rule::AlwaysValid));
+    assert!(!told(&trace, "\"y\""), "the new String was told about:\n{}", trace);
+}
+
+#[test]
+fn moving_a_borrowed_value_is_e0505() {
+    let trace = simulator::simulate("let s = String::from(\"x\");\nlet r = &s;\nlet t = s;\nprintln!(\"{}\", r);\n").unwrap();
+    let error = error(&trace);
+    assert_eq!((error.code, error.line), ("E0505", 3));
+    assert_eq!(error.rule, Some(Rule::AlwaysValid));
+}
+
+#[test]
+fn assigning_twice_to_an_immutable_variable_is_e0384() {
+    let trace = simulator::simulate("let x = 5;\nx = 6;\n").unwrap();
+    let error = error(&trace);
+    assert_eq!((error.code, error.line), ("E0384", 2));
+    assert_eq!(error.rule, None);
+}
+
+#[test]
+fn reports_what_it_cannot_parse_or_run() {
+    let parse = simulator::simulate("let s = String::from(\"x\";\n").unwrap_err();
+    assert_eq!(parse.to_string(), "line 1: expected `)`, found `;`");
+
+    let trace = simulator::simulate("fn main() {\n    main();\n}\n").unwrap();
+    let error = error(&trace);
+    assert_eq!(error.code, "", "a recursion too deep is not a rustc error");
+    assert_eq!(error.message, "`main` calls itself too deeply");
+}
+
+#[test]
+fn reports_a_construct_it_does_not_run_as_unsupported() {
+    let source = "fn main() {\n    let s = String::from(\"hi\");\n    loop {}\n}\n";
+    assert_eq!(simulator::simulate(source).unwrap_err().to_string(), "line 3: `loop` is not supported");
+    assert_eq!(simulator::simulate("return;\n").unwrap_err().to_string(), "line 1: `return` is not supported");
+}
+
+#[test]
+fn a_slice_borrows_the_string_it_points_into() {
+    let source = "let mut s = String::from(\"hello world\");\nlet word = &s[..5];\ns.clear();\nprintln!(\"{}\", word);\n";
+    let trace = simulator::simulate(source).unwrap();
+    assert_eq!((error(&trace).code, error(&trace).line), ("E0502", 3));
+    assert!(told(&trace, "word borrows s[0..5] with &, 5 bytes of heap buffer #1 from byte 0"));
+
+    let trace = simulator::simulate("let s = String::from(\"hello\");\nlet world = &s[6..];\n").unwrap();
+    assert_eq!(error(&trace).message, "`&s[6..5]` panics: \"hello\" is 5 bytes long");
+}
+
+#[test]
+fn steps_draw_the_lines_that_change_memory() {
+    let trace = scenario("cloning", Liveness::NonLexical);
+    let steps: Vec<(usize, &str)> = trace.steps.iter().map(|step| (step.line, step.snapshot.code.as_str())).collect();
+    assert_eq!(steps, [(3, "let s1 = String::from(\"hello\");"), (4, "let s2 = s1.clone();")], "println! changes nothing");
+    assert_eq!(trace.steps[1].snapshot.heap.len(), 2);
+}
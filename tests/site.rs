@@ -0,0 +1,64 @@
+use std::fs;
+use std::path::Path;
+
+use rust_ownership::compile_fail::Annotation;
+use rust_ownership::lesson::CHAPTERS;
+use rust_ownership::site;
+
+#[test]
+fn highlights_the_tokens_of_a_line() {
+    let html = site::highlight(r#"let s: &'static str = "hi"; // a literal"#, &[]);
+    assert_eq!(
+        html,
+        "<span class=\"kw\">let</span> s: &amp;<span class=\"lt\">'static</span> str = \
+         <span class=\"str\">&quot;hi&quot;</span>; <span class=\"com\">// a literal</span>"
+    );
+
+    let html = site::highlight(r#"if item == b' ' { println!("{}", String::new()); }"#, &[]);
+    assert!(html.contains("<span class=\"str\">b' '</span>"));
+    assert!(html.contains("<span class=\"mac\">println!</span>"));
+    assert!(html.contains("<span class=\"ty\">String</span>"));
+}
+
+#[test]
+fn marks_the_lines_of_the_errors() {
+    let error = Annotation { line: 2, code: "E0382".to_string(), message: "borrow of moved value: `s1`".to_string() };
+    let html = site::highlight("let s2 = s1;\nprintln!(\"{}\", s1);", &[error]);
+    let lines: Vec<&str> = html.lines().collect();
+    assert!(!lines[0].contains("marked"));
+    assert!(lines[1].starts_with("<span class=\"marked\" data-code=\"E0382\">"));
+}
+
+#[test]
+fn pages_mark_broken_examples_and_draw_diagrams() {
+    let page = site::page(CHAPTERS[2]);
+    assert!(page.contains("<span class=\"error-code\">E0502</span>"));
+    assert!(page.contains("<span class=\"marked\" data-code=\"E0502\">    <span class=\"kw\">let</span> r3"));
+    assert!(!page.contains("//~"));
+
+    let page = site::page(CHAPTERS[0]);
+    assert_eq!(page.matches("<svg").count(), 4, "two snapshots of bye and two of cloning");
+    let markers: Vec<&str> = page.split("<marker id=\"").skip(1).map(|rest| &rest[..rest.find('"').unwrap()]).collect();
+    assert_eq!(markers, ["ownership-bye-1-head", "ownership-bye-2-head", "ownership-cloning-1-head", "ownership-cloning-2-head"]);
+    assert!(page.contains("<a href=\"the_stack_and_the_heap.html\">The Stack and the Heap &rarr;</a>"));
+    assert!(site::page(CHAPTERS[3]).contains("after <code>let world = &amp;s[6..11];</code>"));
+}
+
+#[test]
+fn writes_pages_that_need_nothing_else() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("site");
+    let _ = fs::remove_dir_all(&dir);
+    let files = site::write(&dir).unwrap();
+    assert_eq!(files.len(), CHAPTERS.len() + 1);
+
+    let index = fs::read_to_string(dir.join("index.html")).unwrap();
+    for chapter in CHAPTERS.iter() {
+        assert!(index.contains(&format!("<a href=\"{}\">", site::file_name(chapter))));
+    }
+    assert!(index.contains("<a href=\"slice_type.html#string-slices\">String Slices</a>"));
+
+    for file in files {
+        let html = fs::read_to_string(&file).unwrap();
+        assert!(!html.contains("<script") && !html.contains("<link") && !html.contains("src="), "{} loads something", file.display());
+    }
+}
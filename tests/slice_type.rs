@@ -0,0 +1,123 @@
+use std::borrow::Cow;
+use std::process::Command;
+
+use rust_ownership::lesson::RUN_LESSON_ENV;
+use rust_ownership::slice_type::{first_word_better_signature, first_word_signature, first_word_unicode};
+
+#[test]
+fn splits_on_ascii_space_like_first_word() {
+    assert_eq!(first_word_unicode("hello world"), "hello");
+    assert_eq!(first_word_unicode("hello"), "hello");
+}
+
+#[test]
+fn splits_on_tabs_and_newlines() {
+    assert_eq!(first_word_unicode("name\tage\tcity"), "name");
+    assert_eq!(first_word_unicode("first\nsecond"), "first");
+    assert_eq!(first_word_unicode("crlf\r\nline"), "crlf");
+}
+
+#[test]
+fn splits_on_unicode_whitespace() {
+    // No-break space, em space, ideographic space and line separator.
+    assert_eq!(first_word_unicode("hello\u{a0}world"), "hello");
+    assert_eq!(first_word_unicode("hello\u{2003}world"), "hello");
+    assert_eq!(first_word_unicode("東京\u{3000}大阪"), "東京");
+    assert_eq!(first_word_unicode("one\u{2028}two"), "one");
+}
+
+#[test]
+fn skips_leading_whitespace() {
+    assert_eq!(first_word_unicode("   hello world"), "hello");
+    assert_eq!(first_word_unicode("\u{a0}\t\nhello"), "hello");
+}
+
+#[test]
+fn keeps_multi_byte_characters_whole() {
+    assert_eq!(first_word_unicode("café crème"), "café");
+    assert_eq!(first_word_unicode("naïve\tidea"), "naïve");
+    assert_eq!(first_word_unicode("こんにちは 世界"), "こんにちは");
+    assert_eq!(first_word_unicode("🦀🦀 rust"), "🦀🦀");
+}
+
+#[test]
+fn keeps_combining_marks_and_joined_emoji_whole() {
+    // "é" written as "e" followed by a combining acute accent.
+    assert_eq!(first_word_unicode("cafe\u{301} noir"), "cafe\u{301}");
+    // A family emoji made of four emoji joined by zero width joiners.
+    let family = "👩\u{200d}👩\u{200d}👧\u{200d}👦";
+    assert_eq!(first_word_unicode(&format!("{} home", family)), family);
+}
+
+#[test]
+fn returns_an_empty_word_when_there_is_none() {
+    assert_eq!(first_word_unicode(""), "");
+    assert_eq!(first_word_unicode(" \t\u{a0}\u{3000}"), "");
+}
+
+// first_word_better_signature takes a &str, so anything that derefs to str can be passed to it.
+
+#[test]
+fn string_signature_takes_a_reference_to_a_string() {
+    let s = String::from("hello world");
+    assert_eq!(first_word_signature(&s), "hello");
+    assert_eq!(first_word_signature(&String::from("single")), "single");
+}
+
+#[test]
+fn str_signature_takes_a_string_slice() {
+    let s = String::from("hello world");
+    assert_eq!(first_word_better_signature(&s[..]), "hello");
+    assert_eq!(first_word_better_signature(s.as_str()), "hello");
+}
+
+#[test]
+fn str_signature_takes_a_reference_to_a_string() {
+    let s = String::from("hello world");
+    let reference: &String = &s;
+    assert_eq!(first_word_better_signature(reference), "hello");
+}
+
+#[test]
+fn str_signature_takes_a_string_literal() {
+    assert_eq!(first_word_better_signature("hello world"), "hello");
+    assert_eq!(first_word_better_signature("hello"), "hello");
+}
+
+#[test]
+fn str_signature_takes_a_sub_slice() {
+    let s = String::from("hello brave new world");
+    assert_eq!(first_word_better_signature(&s[6..]), "brave");
+    assert_eq!(first_word_better_signature(&s[6..9]), "bra");
+    assert_eq!(first_word_better_signature(&"hello world"[6..]), "world");
+}
+
+#[test]
+fn str_signature_takes_a_boxed_str() {
+    let boxed: Box<str> = Box::from("hello world");
+    assert_eq!(first_word_better_signature(&boxed), "hello");
+}
+
+#[test]
+fn str_signature_takes_a_cow() {
+    let borrowed: Cow<str> = Cow::Borrowed("hello world");
+    let owned: Cow<str> = Cow::Owned(String::from("goodbye world"));
+    assert_eq!(first_word_better_signature(&borrowed), "hello");
+    assert_eq!(first_word_better_signature(&owned), "goodbye");
+}
+
+#[test]
+fn both_signatures_agree() {
+    for text in ["hello world", "hello", "", " leading", "trailing "] {
+        let s = String::from(text);
+        assert_eq!(first_word_signature(&s), first_word_better_signature(&s));
+    }
+}
+
+// main_index runs in the binary, the way `rust-ownership run` runs it.
+#[test]
+fn index_outlives_the_string_it_came_from() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-ownership")).env(RUN_LESSON_ENV, "slice_type::main_index").output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "after clear, s[0..5] is None\nthe first word is: goodb\n");
+}
@@ -0,0 +1,49 @@
+use rust_ownership::slices::{find_slice, find_window, first_run, first_word, pairs, split_at_value, words};
+
+#[test]
+fn finds_the_first_word_of_a_byte_buffer() {
+    assert_eq!(first_word(b"hello world", &b' '), b"hello");
+    assert_eq!(first_word(b"hello", &b' '), b"hello");
+    assert_eq!(first_word(&[1, 2, 0, 3], &0), &[1, 2]);
+    assert_eq!(first_word::<i32>(&[], &0), &[] as &[i32]);
+}
+
+#[test]
+fn splits_words_on_any_separator() {
+    let a = [0, 1, 2, 0, 0, 3, 0];
+    let found: Vec<&[i32]> = words(&a, &0).collect();
+    assert_eq!(found, [&[1, 2][..], &[3][..]]);
+}
+
+#[test]
+fn takes_the_first_run() {
+    let a = [2, 4, 6, 7, 8];
+    assert_eq!(first_run(&a, |n| n % 2 == 0), &[2, 4, 6]);
+    assert_eq!(first_run(&a, |n| *n > 10), &[] as &[i32]);
+    assert_eq!(first_run(&a, |_| true), &a);
+}
+
+#[test]
+fn splits_at_a_value() {
+    let line = b"key=value=more";
+    assert_eq!(split_at_value(line, &b'='), Some((&b"key"[..], &b"value=more"[..])));
+    assert_eq!(split_at_value(b"no separator", &b'='), None);
+    assert_eq!(split_at_value(&[1, 2, 3], &3), Some((&[1, 2][..], &[][..])));
+}
+
+#[test]
+fn finds_sub_slices_and_windows() {
+    let request = b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    assert_eq!(find_slice(request, b"\r\n"), Some(14));
+    assert_eq!(find_slice(request, b"\r\n\r\n"), Some(request.len() - 4));
+    assert_eq!(find_slice(request, b"POST"), None);
+    assert_eq!(find_slice(request, b""), Some(0));
+
+    let a = [1, 5, 2, 8, 9, 3];
+    assert_eq!(find_window(&a, 2, |w| w[0] + w[1] > 15), Some(&[8, 9][..]));
+    assert_eq!(find_window(&a, 7, |_| true), None);
+    assert_eq!(find_window(&a, 0, |_| true), None);
+
+    let rising = pairs(&a).filter(|(x, y)| x < y).count();
+    assert_eq!(rising, 3);
+}
@@ -0,0 +1,43 @@
+use rust_ownership::words::{last_word, nth_word, second_word, words};
+
+#[test]
+fn finds_words_by_position() {
+    let s = "the quick\tbrown\u{a0}fox";
+    assert_eq!(nth_word(s, 0), Some("the"));
+    assert_eq!(second_word(s), Some("quick"));
+    assert_eq!(nth_word(s, 2), Some("brown"));
+    assert_eq!(last_word(s), Some("fox"));
+    assert_eq!(nth_word(s, 4), None);
+}
+
+#[test]
+fn has_no_words_in_blank_strings() {
+    assert_eq!(second_word("one"), None);
+    assert_eq!(last_word(""), None);
+    assert_eq!(words(" \t\u{3000} ").count(), 0);
+}
+
+#[test]
+fn gives_byte_ranges_into_the_string() {
+    let s = "  café 東京\t🦀 ";
+    let found: Vec<_> = words(s).collect();
+    assert_eq!(found, vec![(2..7, "café"), (8..14, "東京"), (15..19, "🦀")]);
+    for (range, word) in found {
+        assert_eq!(&s[range], word);
+    }
+}
+
+#[test]
+fn iterates_from_both_ends() {
+    let s = "one two three four";
+    let backwards: Vec<_> = words(s).rev().map(|(_, word)| word).collect();
+    assert_eq!(backwards, ["four", "three", "two", "one"]);
+
+    let mut iter = words(s);
+    assert_eq!(iter.next().map(|(_, word)| word), Some("one"));
+    assert_eq!(iter.next_back().map(|(_, word)| word), Some("four"));
+    assert_eq!(iter.next().map(|(_, word)| word), Some("two"));
+    assert_eq!(iter.next_back().map(|(_, word)| word), Some("three"));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+}